@@ -120,14 +120,394 @@ mod public_key_set_benches {
         group.finish();
     }
 
+    /// Benchmarks computing the public key shares of a large set, one by one and batched.
+    fn bench_all_public_key_shares(c: &mut Criterion) {
+        let mut rng = XorShiftRng::from_seed(RNG_SEED);
+        let (n, threshold) = (1000, 100);
+        let pk_set = SecretKeySet::random(threshold, &mut rng).public_keys();
+        let mut group = c.benchmark_group("all_public_key_shares");
+        group.sample_size(10);
+        group.bench_function("per_index", |b| {
+            b.iter(|| {
+                (0..n)
+                    .map(|i| pk_set.public_key_share(i))
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_function("batched", |b| b.iter(|| pk_set.all_public_key_shares(n)));
+        group.finish();
+    }
+
     criterion_group! {
         name = public_key_set_benches;
         config = Criterion::default();
-        targets = bench_combine_signatures,
+        targets = bench_combine_signatures, bench_all_public_key_shares,
+    }
+}
+
+mod sig_benches {
+    use super::*;
+    use bls12_381::{
+        multi_miller_loop, pairing, G1Affine, G2Prepared, G2Projective, MillerLoopResult,
+    };
+    use group::Curve;
+    use rust_tc::{aggregate, hash_g2, verify_messages, PreparedHashes, SecretKey};
+
+    const TEST_MESSAGE_COUNTS: [usize; 3] = [10, 50, 200];
+    const REPEATS: usize = 5;
+
+    /// Benchmarks repeatedly verifying the same aggregate signature, with and without prepared
+    /// hashes.
+    fn bench_repeated_aggregate_verify(c: &mut Criterion) {
+        let mut group = c.benchmark_group("repeated_aggregate_verify");
+        for count in TEST_MESSAGE_COUNTS.iter() {
+            let sks: Vec<_> = (0..*count).map(|_| SecretKey::random()).collect();
+            let pks: Vec<_> = sks.iter().map(SecretKey::public_key).collect();
+            let msgs: Vec<Vec<u8>> = (0..*count).map(|i| i.to_be_bytes().to_vec()).collect();
+            let msgs: Vec<&[u8]> = msgs.iter().map(Vec::as_slice).collect();
+            let sigs: Vec<_> = sks.iter().zip(&msgs).map(|(sk, m)| sk.sign(m)).collect();
+            let sig = aggregate(&sigs).expect("unable to aggregate");
+
+            group.bench_with_input(BenchmarkId::new("naive", count), count, |b, _| {
+                b.iter(|| {
+                    for _ in 0..REPEATS {
+                        assert!(verify_messages(&sig, &msgs, &pks).unwrap());
+                    }
+                })
+            });
+            group.bench_with_input(BenchmarkId::new("prepared", count), count, |b, _| {
+                b.iter(|| {
+                    let prepared = PreparedHashes::from_messages(&msgs).unwrap();
+                    for _ in 0..REPEATS {
+                        assert!(prepared.verify(&sig, &pks).unwrap());
+                    }
+                })
+            });
+        }
+        group.finish();
+    }
+
+    /// Benchmarks verifying an aggregate signature of distinct messages: the pairing equation with
+    /// one Miller loop per pair and a separate pairing for the signature, and `verify_messages`,
+    /// which uses a single multi-Miller loop.
+    fn bench_aggregate_verify(c: &mut Criterion) {
+        let mut group = c.benchmark_group("aggregate_verify");
+        for count in TEST_MESSAGE_COUNTS.iter() {
+            let sks: Vec<_> = (0..*count).map(|_| SecretKey::random()).collect();
+            let pks: Vec<_> = sks.iter().map(SecretKey::public_key).collect();
+            let msgs: Vec<Vec<u8>> = (0..*count).map(|i| i.to_be_bytes().to_vec()).collect();
+            let msgs: Vec<&[u8]> = msgs.iter().map(Vec::as_slice).collect();
+            let sigs: Vec<_> = sks.iter().zip(&msgs).map(|(sk, m)| sk.sign(m)).collect();
+            let sig = aggregate(&sigs).expect("unable to aggregate");
+
+            group.bench_with_input(BenchmarkId::new("per_pair_loops", count), count, |b, _| {
+                b.iter(|| {
+                    let lhs = pks
+                        .iter()
+                        .zip(&msgs)
+                        .map(|(pk, msg)| {
+                            let hash = G2Prepared::from(hash_g2(msg).to_affine());
+                            multi_miller_loop(&[(&pk.0.to_affine(), &hash)])
+                        })
+                        .fold(MillerLoopResult::default(), |acc, cur| acc + cur)
+                        .final_exponentiation();
+                    assert_eq!(lhs, pairing(&G1Affine::generator(), &sig.0.to_affine()))
+                })
+            });
+            group.bench_with_input(BenchmarkId::new("verify_messages", count), count, |b, _| {
+                b.iter(|| assert!(verify_messages(&sig, &msgs, &pks).unwrap()))
+            });
+        }
+        group.finish();
+    }
+
+    /// Benchmarks the duplicate check on message hashes: the former pairwise comparison on its
+    /// own, and `PreparedHashes::new`, which checks in linear time before preparing the hashes.
+    fn bench_unique_hashes(c: &mut Criterion) {
+        let mut group = c.benchmark_group("unique_hashes");
+        for count in [100, 1000].iter() {
+            let mut hashes = Vec::with_capacity(*count);
+            let mut h = hash_g2("Test message");
+            for _ in 0..*count {
+                hashes.push(h);
+                h += G2Projective::generator();
+            }
+
+            group.bench_with_input(BenchmarkId::new("pairwise", count), count, |b, _| {
+                b.iter(|| {
+                    for i in 0..hashes.len() {
+                        for j in (i + 1)..hashes.len() {
+                            assert_ne!(hashes[i], hashes[j]);
+                        }
+                    }
+                })
+            });
+            group.bench_with_input(BenchmarkId::new("prepared_hashes", count), count, |b, _| {
+                b.iter(|| PreparedHashes::new(&hashes).unwrap())
+            });
+        }
+        group.finish();
+    }
+
+    criterion_group! {
+        name = sig_benches;
+        config = Criterion::default();
+        targets = bench_repeated_aggregate_verify, bench_aggregate_verify, bench_unique_hashes,
+    }
+}
+
+mod pk_benches {
+    use super::*;
+    use bls12_381::{multi_miller_loop, pairing, G1Affine, G2Prepared, G2Projective};
+    use group::{Curve, Group};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    use rust_tc::{hash_g2, SecretKey, SecretKeySet, Signature};
+
+    /// Benchmarks verifying a single signature: the pairing equation on its own, computed with two
+    /// pairings and with one multi-Miller loop, and `PublicKey::verify` including the hash.
+    fn bench_verify(c: &mut Criterion) {
+        let mut rng = XorShiftRng::from_seed(RNG_SEED);
+        let sk = SecretKey::random();
+        let pk = sk.public_key();
+        let msg = "Test message";
+        let sig = sk.sign(msg);
+
+        let hash = G2Projective::random(&mut rng);
+        let pk_affine = pk.0.to_affine();
+        let hash_affine = hash.to_affine();
+        let sig_affine = (hash * sk.0).to_affine();
+
+        let mut group = c.benchmark_group("verify");
+        group.bench_function("two_pairings", |b| {
+            b.iter(|| {
+                assert_eq!(
+                    pairing(&G1Affine::generator(), &sig_affine),
+                    pairing(&pk_affine, &hash_affine)
+                )
+            })
+        });
+        group.bench_function("multi_miller_loop", |b| {
+            b.iter(|| {
+                let sig = G2Prepared::from(sig_affine);
+                let hash = G2Prepared::from(hash_affine);
+                let gt = multi_miller_loop(&[(&-G1Affine::generator(), &sig), (&pk_affine, &hash)])
+                    .final_exponentiation();
+                assert!(bool::from(gt.is_identity()))
+            })
+        });
+        group.bench_function("public_key_verify", |b| {
+            b.iter(|| assert!(pk.verify(&sig, msg)))
+        });
+        group.finish();
+    }
+
+    /// Benchmarks verifying many signatures under one key, one by one and as a batch.
+    fn bench_verify_batch(c: &mut Criterion) {
+        let mut rng = XorShiftRng::from_seed(RNG_SEED);
+        let sk = SecretKey::random();
+        let pk = sk.public_key();
+        let mut group = c.benchmark_group("verify_batch");
+        for count in [10, 50].iter() {
+            let msgs: Vec<Vec<u8>> = (0..*count as u32)
+                .map(|i| i.to_be_bytes().to_vec())
+                .collect();
+            let sigs: Vec<_> = msgs.iter().map(|msg| sk.sign(msg)).collect();
+            let items: Vec<(&[u8], &Signature)> =
+                msgs.iter().map(Vec::as_slice).zip(&sigs).collect();
+
+            group.bench_with_input(BenchmarkId::new("naive", count), count, |b, _| {
+                b.iter(|| assert!(items.iter().all(|(msg, sig)| pk.verify(sig, msg))))
+            });
+            group.bench_with_input(BenchmarkId::new("batch", count), count, |b, _| {
+                b.iter(|| assert!(pk.verify_batch(&items, &mut rng)))
+            });
+        }
+        group.finish();
+    }
+
+    /// Benchmarks verifying 100 signature shares of one message, hashing it for each share and
+    /// hashing it once.
+    fn bench_verify_hash(c: &mut Criterion) {
+        let mut rng = XorShiftRng::from_seed(RNG_SEED);
+        let sk_set = SecretKeySet::random(33, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = "Test message";
+        let shares: Vec<_> = (0..100usize)
+            .map(|i| {
+                let sig = sk_set.secret_key_share(i).sign(msg);
+                (pk_set.public_key_share(i), sig)
+            })
+            .collect();
+
+        let mut group = c.benchmark_group("verify_shares");
+        group.bench_function("verify", |b| {
+            b.iter(|| assert!(shares.iter().all(|(pk, sig)| pk.verify(sig, msg))))
+        });
+        group.bench_function("verify_hash", |b| {
+            b.iter(|| {
+                let hash = hash_g2(msg).to_affine();
+                assert!(shares.iter().all(|(pk, sig)| pk.verify_hash(sig, &hash)))
+            })
+        });
+        group.finish();
+    }
+
+    criterion_group! {
+        name = pk_benches;
+        config = Criterion::default();
+        targets = bench_verify, bench_verify_batch, bench_verify_hash,
+    }
+}
+
+mod ciphertext_benches {
+    use super::*;
+    use bls12_381::{multi_miller_loop, pairing, G1Affine, G2Prepared, G2Projective};
+    use criterion::BatchSize;
+    use group::{Curve, Group};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    use rust_tc::{Ciphertext, SecretKey, SecretKeySet};
+    use std::cmp::Ordering;
+    use std::collections::BTreeSet;
+
+    /// Benchmarks verifying a ciphertext: the pairing equation `e(g, w) == e(u, hash)` on its own,
+    /// computed with two pairings and with one multi-Miller loop, and `Ciphertext::verify`
+    /// including the hash.
+    fn bench_ciphertext_verify(c: &mut Criterion) {
+        let mut rng = XorShiftRng::from_seed(RNG_SEED);
+        let pk = SecretKey::random().public_key();
+        let ct = pk.encrypt_with_rng(&mut rng, "Test message");
+
+        let r = Scalar::random(&mut rng);
+        let hash = G2Projective::random(&mut rng);
+        let u_affine = (G1Affine::generator() * r).to_affine();
+        let hash_affine = hash.to_affine();
+        let w_affine = (hash * r).to_affine();
+
+        let mut group = c.benchmark_group("ciphertext_verify");
+        group.bench_function("two_pairings", |b| {
+            b.iter(|| {
+                assert_eq!(
+                    pairing(&G1Affine::generator(), &w_affine),
+                    pairing(&u_affine, &hash_affine)
+                )
+            })
+        });
+        group.bench_function("multi_miller_loop", |b| {
+            b.iter(|| {
+                let w = G2Prepared::from(w_affine);
+                let hash = G2Prepared::from(hash_affine);
+                let gt = multi_miller_loop(&[(&-G1Affine::generator(), &w), (&u_affine, &hash)])
+                    .final_exponentiation();
+                assert!(bool::from(gt.is_identity()))
+            })
+        });
+        group.bench_function("ciphertext_verify", |b| b.iter(|| assert!(ct.verify())));
+        group.finish();
+    }
+
+    /// Benchmarks verifying 50 ciphertexts one by one and with `Ciphertext::verify_batch`.
+    fn bench_ciphertext_verify_batch(c: &mut Criterion) {
+        let mut rng = XorShiftRng::from_seed(RNG_SEED);
+        let pk = SecretKey::random().public_key();
+        let cts: Vec<Ciphertext> = (0..50u8)
+            .map(|i| pk.encrypt_with_rng(&mut rng, [i]))
+            .collect();
+
+        let mut group = c.benchmark_group("ciphertext_verify_batch");
+        group.sample_size(10);
+        group.bench_function("sequential", |b| {
+            b.iter(|| assert!(cts.iter().all(Ciphertext::verify)))
+        });
+        group.bench_function("batch", |b| {
+            b.iter(|| assert!(Ciphertext::verify_batch(&cts, &mut rng)))
+        });
+        group.finish();
+    }
+
+    /// Benchmarks computing a decryption share, with and without verifying the ciphertext.
+    fn bench_decrypt_share(c: &mut Criterion) {
+        let mut rng = XorShiftRng::from_seed(RNG_SEED);
+        let sk_share = SecretKeySet::random(3, &mut rng).secret_key_share(0);
+        let ct = sk_share
+            .public_key_share()
+            .0
+            .encrypt_with_rng(&mut rng, "Test message");
+        let verified = ct.clone().into_verified().unwrap();
+
+        let mut group = c.benchmark_group("decrypt_share");
+        group.bench_function("checked", |b| {
+            b.iter(|| sk_share.try_decrypt_share(&ct).unwrap())
+        });
+        group.bench_function("verified", |b| {
+            b.iter(|| sk_share.decrypt_share_verified(&verified))
+        });
+        group.finish();
+    }
+
+    /// A ciphertext ordered by compressing its points on every comparison, as it was before
+    /// `Ciphertext` cached its encodings.
+    #[derive(PartialEq, Eq)]
+    struct Uncached(Ciphertext);
+
+    impl PartialOrd for Uncached {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Uncached {
+        fn cmp(&self, other: &Self) -> Ordering {
+            let (a, b) = (&self.0, &other.0);
+            let u = |ct: &Ciphertext| ct.u().to_affine().to_compressed();
+            let w = |ct: &Ciphertext| ct.w().to_affine().to_compressed();
+            u(a).cmp(&u(b))
+                .then(a.v().cmp(b.v()))
+                .then(w(a)[..].cmp(&w(b)[..]))
+        }
+    }
+
+    /// Benchmarks inserting 10 000 ciphertexts into a `BTreeSet`, with the cached encodings and
+    /// with encodings computed on every comparison.
+    fn bench_ciphertext_set(c: &mut Criterion) {
+        let mut rng = XorShiftRng::from_seed(RNG_SEED);
+        let pk = SecretKey::random().public_key();
+        let cts: Vec<Ciphertext> = (0..10_000u32)
+            .map(|i| pk.encrypt_with_rng(&mut rng, i.to_be_bytes()))
+            .collect();
+
+        let mut group = c.benchmark_group("ciphertext_set");
+        group.sample_size(10);
+        group.bench_function("uncached", |b| {
+            b.iter_batched(
+                || cts.iter().cloned().map(Uncached).collect::<Vec<_>>(),
+                |cts| cts.into_iter().collect::<BTreeSet<_>>(),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_function("cached", |b| {
+            b.iter_batched(
+                || cts.clone(),
+                |cts| cts.into_iter().collect::<BTreeSet<_>>(),
+                BatchSize::LargeInput,
+            )
+        });
+        group.finish();
+    }
+
+    criterion_group! {
+        name = ciphertext_benches;
+        config = Criterion::default();
+        targets = bench_ciphertext_verify, bench_ciphertext_verify_batch, bench_decrypt_share,
+            bench_ciphertext_set,
     }
 }
 
 criterion_main!(
     poly_benches::poly_benches,
-    public_key_set_benches::public_key_set_benches
+    public_key_set_benches::public_key_set_benches,
+    sig_benches::sig_benches,
+    pk_benches::pk_benches,
+    ciphertext_benches::ciphertext_benches
 );
@@ -0,0 +1,9 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+// The first byte selects the type, the rest is its encoding. No input may panic.
+fuzz_target!(|data: &[u8]| {
+    if let Some((tag, bytes)) = data.split_first() {
+        let _ = rust_tc::parse_any(*tag, bytes);
+    }
+});
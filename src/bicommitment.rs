@@ -84,6 +84,14 @@ impl BivarCommitment {
         Commitment { coeff }
     }
 
+    /// Returns `true` if `row_commit` is the commitment to the `x`-th row, i.e. equals `row(x)`.
+    ///
+    /// This is the check a DKG node runs on the row it received from a dealer: if it fails, the
+    /// dealer cheated and the node should complain. Trailing identity coefficients are ignored.
+    pub fn verify_row<T: IntoScalar>(&self, x: T, row_commit: &Commitment) -> bool {
+        self.row(x).diff_positions(row_commit).is_empty()
+    }
+
     /// Returns the `0`-th to `degree`-th power of `x`.
     fn powers<T: IntoScalar>(&self, x: T) -> Vec<Scalar> {
         powers(x, self.degree)
@@ -99,3 +107,24 @@ impl BivarCommitment {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{BivarPoly, Poly};
+    use bls12_381::Scalar;
+
+    #[test]
+    fn verify_row() {
+        let bi_poly = BivarPoly::random(3);
+        let bi_commit = bi_poly.commitment();
+        for m in 1..=4 {
+            let row_poly = bi_poly.row(m);
+            assert!(bi_commit.verify_row(m, &row_poly.commitment()));
+            assert!(!bi_commit.verify_row(m + 1, &row_poly.commitment()));
+
+            // A cheating dealer who adds `5 X²` to the row is detected.
+            let wrong_poly = row_poly + Poly::monomial(2) * Poly::constant(Scalar::from(5));
+            assert!(!bi_commit.verify_row(m, &wrong_poly.commitment()));
+        }
+    }
+}
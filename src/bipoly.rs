@@ -1,9 +1,10 @@
-use crate::util::{clear_scalar, coeff_pos, powers};
-use crate::{BivarCommitment, IntoScalar, Poly};
+use crate::util::{clear_scalar, coeff_pos, powers, try_coeff_count, Redacted};
+use crate::{BivarCommitment, Error, IntoScalar, Poly};
 use anyhow::{bail, Result};
 use bls12_381::{G1Affine, G1Projective, Scalar};
 use ff::Field;
 use rand::Rng;
+use std::fmt;
 use std::iter::repeat_with;
 use zeroize::Zeroize;
 
@@ -11,7 +12,10 @@ use zeroize::Zeroize;
 ///
 /// This can be used for Verifiable Secret Sharing and Distributed Key Generation. See the module
 /// documentation for details.
-#[derive(Clone, Debug)]
+///
+/// Its `Debug` output only shows the degree, unless the `debug-secrets` feature is enabled; use
+/// `reveal` to print the coefficients on purpose.
+#[derive(Clone)]
 pub struct BivarPoly {
     /// The polynomial's degree in each of the two variables.
     degree: usize,
@@ -20,6 +24,18 @@ pub struct BivarPoly {
     coeff: Vec<Scalar>,
 }
 
+impl fmt::Debug for BivarPoly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = f.debug_struct("BivarPoly");
+        s.field("degree", &self.degree);
+        if cfg!(feature = "debug-secrets") {
+            s.field("coeff", &self.coeff).finish()
+        } else {
+            s.field("coeff", &Redacted).finish()
+        }
+    }
+}
+
 impl Zeroize for BivarPoly {
     fn zeroize(&mut self) {
         for scalar in self.coeff.iter_mut() {
@@ -36,21 +52,50 @@ impl Drop for BivarPoly {
 }
 
 impl BivarPoly {
-    /// Creates a random polynomial.
+    /// Creates a random polynomial. This constructor is identical to the `BivarPoly::try_random()`
+    /// in every way except that this constructor panics if the other returns an error.
     ///
     /// # Panics
     ///
     /// Panics if the degree is too high for the coefficients to fit into a `Vec`.
     pub fn random(degree: usize) -> Self {
-        let len = coeff_pos(degree, degree).and_then(|l| l.checked_add(1));
+        BivarPoly::try_random(degree)
+            .unwrap_or_else(|e| panic!("Failed to create random `BivarPoly`: {}", e))
+    }
+
+    /// Creates a random polynomial. Returns an `Error::DegreeOverflow` if the degree is too high
+    /// for the number of coefficients to be represented.
+    pub fn try_random(degree: usize) -> Result<Self> {
+        let len = try_coeff_count(degree)?;
 
         let coeff: Vec<Scalar> = repeat_with(|| {
             let rng = rand::thread_rng();
             Scalar::random(rng)
         })
-        .take(len.unwrap())
+        .take(len)
         .collect();
-        BivarPoly { degree, coeff }
+        Ok(BivarPoly { degree, coeff })
+    }
+
+    /// Creates the polynomial of the given degree with the given coefficients, e.g. for
+    /// reproducible tests and fixtures.
+    ///
+    /// Coefficient `(i, j)` for `i <= j`, i.e. that of `x^i y^j` and `x^j y^i`, is in position
+    /// `j * (j + 1) / 2 + i`. Fails if the number of coefficients doesn't match the degree.
+    pub fn from_coefficients(degree: usize, coeffs: &[Scalar]) -> Result<Self> {
+        let len = try_coeff_count(degree)?;
+        if coeffs.len() != len {
+            bail!(
+                "Invalid number of coefficients {} for degree {}, expected {}",
+                coeffs.len(),
+                degree,
+                len
+            )
+        }
+        Ok(BivarPoly {
+            degree,
+            coeff: coeffs.to_vec(),
+        })
     }
 
     /// Creates a polynomial where the 0th coeff is set to `secret`.
@@ -86,21 +131,29 @@ impl BivarPoly {
     }
 
     /// Returns the `x`-th row, as a univariate polynomial.
+    ///
+    /// The powers of `x` and the intermediate products are cleared before returning.
     pub fn row<T: IntoScalar>(&self, x: T) -> Poly {
-        let x_pow = self.powers(x);
+        self.row_from_powers(&mut self.powers(x))
+    }
+
+    /// Returns the row at the `x` whose powers are `x_pow`, and clears `x_pow`.
+    fn row_from_powers(&self, x_pow: &mut [Scalar]) -> Poly {
         let coeff: Vec<Scalar> = (0..=self.degree)
             .map(|i| {
-                // TODO: clear these secrets from the stack.
                 let mut result = Scalar::zero();
+                let mut summand = Scalar::zero();
                 for (j, x_pow_j) in x_pow.iter().enumerate() {
                     let index = coeff_pos(i, j).expect("polynomial degree too high");
-                    let mut summand = self.coeff[index];
+                    summand = self.coeff[index];
                     summand *= x_pow_j;
                     result += &summand;
                 }
+                clear_scalar(&mut summand);
                 result
             })
             .collect();
+        x_pow.iter_mut().for_each(clear_scalar);
         Poly::from(coeff)
     }
 
@@ -135,6 +188,22 @@ mod tests {
     use bls12_381::{G1Affine, G1Projective};
     use std::collections::BTreeMap;
 
+    #[test]
+    fn from_coefficients() {
+        // `1 + 2x + 2y + 3xy`
+        let coeffs: Vec<Scalar> = [1u64, 2, 3].iter().map(|c| Scalar::from(*c)).collect();
+        let bi_poly = BivarPoly::from_coefficients(1, &coeffs).unwrap();
+        assert_eq!(1, bi_poly.degree());
+        assert_eq!(Scalar::from(29), bi_poly.evaluate(2, 3));
+        assert_eq!(bi_poly.evaluate(3, 2), bi_poly.evaluate(2, 3));
+        let row: Vec<Scalar> = [3u64, 5].iter().map(|c| Scalar::from(*c)).collect();
+        assert_eq!(Poly::from(row), bi_poly.row(1));
+
+        assert!(BivarPoly::from_coefficients(1, &coeffs[..2]).is_err());
+        assert!(BivarPoly::from_coefficients(2, &coeffs).is_err());
+        assert!(BivarPoly::from_coefficients(0, &coeffs[..1]).is_ok());
+    }
+
     #[test]
     fn bipoly_with_secret() {
         let degree: usize = 3;
@@ -143,6 +212,26 @@ mod tests {
         assert_eq!(secret.into_scalar(), bipoly_with_secret.coeff[0])
     }
 
+    #[test]
+    fn try_random_degree_overflow() {
+        let err = BivarPoly::try_random(usize::MAX / 2).unwrap_err();
+        assert_eq!(Some(&Error::DegreeOverflow), err.downcast_ref::<Error>());
+        assert!(BivarPoly::try_random(3).is_ok());
+    }
+
+    #[test]
+    fn row_clears_powers() {
+        let bi_poly = BivarPoly::random(3);
+        let mut x_pow = bi_poly.powers(5);
+        assert!(x_pow.iter().all(|x| *x != Scalar::zero()));
+        let row = bi_poly.row_from_powers(&mut x_pow);
+        assert!(x_pow.iter().all(|x| *x == Scalar::zero()));
+        assert_eq!(bi_poly.row(5), row);
+        for y in 0..4 {
+            assert_eq!(bi_poly.evaluate(5, y), row.evaluate(y));
+        }
+    }
+
     #[test]
     fn test_zeroize() {
         let mut poly = Poly::monomial(3) + Poly::monomial(2) - 1;
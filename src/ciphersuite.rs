@@ -0,0 +1,209 @@
+//! The basic scheme of the IRTF BLS signature draft, with the ciphersuite
+//! `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_`.
+//!
+//! Public keys are in G1 and signatures in G2, as in the rest of this crate, but messages are
+//! hashed with RFC 9380 hash-to-curve and the suite's domain separation tag, and all inputs are
+//! octet strings. Signatures produced here verify in blst, py_ecc and other implementations of
+//! the draft, and vice versa. `SecretKey::sign` uses the same hash with the `hash-to-curve`
+//! feature, but `PublicKey::verify` doesn't validate its inputs as required here.
+
+use crate::util::hash_to_g2;
+use crate::{PublicKey, SecretKey, Signature};
+use anyhow::{bail, Result};
+use bls12_381::{multi_miller_loop, G1Affine, G2Prepared, G2Projective};
+use group::{Curve, Group};
+use std::collections::HashSet;
+
+/// The ciphersuite identifier, used as the domain separation tag for hashing messages.
+pub const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// Decodes a compressed public key, following `KeyValidate`: fails unless it is a point in the G1
+/// subgroup other than the identity.
+pub fn key_validate(pk: &[u8]) -> Result<PublicKey> {
+    let pk = PublicKey::from_bytes(pk)?;
+    if !pk.is_valid() {
+        bail!("Public key is the identity")
+    }
+    Ok(pk)
+}
+
+/// Returns the compressed public key of `sk`, following `SkToPk`.
+pub fn sk_to_pk(sk: &SecretKey) -> [u8; 48] {
+    sk.public_key().to_bytes()
+}
+
+/// Returns the compressed signature of `msg`, following `Sign`.
+pub fn sign(sk: &SecretKey, msg: &[u8]) -> [u8; 96] {
+    (hash_to_g2(msg, DST) * sk.0).to_affine().to_compressed()
+}
+
+/// Returns `true` if `sig` is a valid signature of `msg` by `pk`, following `Verify`.
+///
+/// Both the public key and the signature must decode to points in their subgroups.
+pub fn verify(pk: &[u8], msg: &[u8], sig: &[u8]) -> bool {
+    aggregate_verify(&[pk], &[msg], sig)
+}
+
+/// Returns the sum of the compressed signatures, following `Aggregate`.
+///
+/// Fails if `sigs` is empty or any signature is not a point in the G2 subgroup.
+pub fn aggregate(sigs: &[&[u8]]) -> Result<[u8; 96]> {
+    if sigs.is_empty() {
+        bail!("Cannot aggregate an empty list of signatures")
+    }
+    let mut sum = G2Projective::identity();
+    for sig in sigs {
+        sum += Signature::from_bytes(sig)?.0;
+    }
+    Ok(sum.to_affine().to_compressed())
+}
+
+/// Returns `true` if `sig` is the aggregate of signatures of `msgs[i]` by `pks[i]`, following
+/// `AggregateVerify` of the basic scheme.
+///
+/// The messages must be pairwise distinct, and every public key must pass `key_validate`.
+pub fn aggregate_verify(pks: &[&[u8]], msgs: &[&[u8]], sig: &[u8]) -> bool {
+    if pks.is_empty() || pks.len() != msgs.len() {
+        return false;
+    }
+    let mut seen = HashSet::new();
+    if !msgs.iter().all(|msg| seen.insert(*msg)) {
+        return false;
+    }
+    let sig = match Signature::from_bytes(sig) {
+        Ok(sig) => G2Prepared::from(sig.0.to_affine()),
+        Err(_) => return false,
+    };
+    let mut terms = Vec::with_capacity(pks.len() + 1);
+    for (pk, msg) in pks.iter().zip(msgs) {
+        let pk = match key_validate(pk) {
+            Ok(pk) => pk.0.to_affine(),
+            Err(_) => return false,
+        };
+        let hash = G2Prepared::from(hash_to_g2(msg, DST).to_affine());
+        terms.push((pk, hash));
+    }
+    let neg_g1 = -G1Affine::generator();
+    let mut pairs: Vec<_> = terms.iter().map(|(pk, hash)| (pk, hash)).collect();
+    pairs.push((&neg_g1, &sig));
+    multi_miller_loop(&pairs)
+        .final_exponentiation()
+        .is_identity()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// Big-endian secret key, public key, message and signature, generated with blst's `min_pk`
+    /// module and the `NUL` tag.
+    const VECTORS: [(&str, &str, &[u8], &str); 3] = [
+        (
+            "2a00000000000000000000000000001100000000000000000000000000000001",
+            "a28f7bad7f1b4d17fbfbe7d27ab1a985739e0dd480f55c68e7e601ce052ea25c1249f075dab8e8d40791a98d8bff00bb",
+            b"",
+            concat!(
+                "96b78f59be7f8c74be0cbe3ff3575de305c8731d502251f5c9666f6a043c176191acaa8c45cebe679bd9fc76443c7278",
+                "16da91ef234d7dbd0631ae79f204e9d4edeaee3b3e54874b29ed665a77fdefe4998e1a2b566c4541590eee529f672466",
+            ),
+        ),
+        (
+            "2a00000000000000000000000000002200000000000000000000000000000002",
+            "8692dfebb685f75c5fe243fd192772a021d79c90614f0d042044f7c7d264f2b3807a95cbc2a205a00b53b285cc28cb27",
+            b"abc",
+            concat!(
+                "905979dce5d243fdb6ab1452794c900f5cca8c56c74df627afce1953dadcbf5d6a8054885b9cf46717ebf272ed5a2666",
+                "081bfe6cbcae61cc13617de02fd03cb2ae9a11c69b556f15e832e5356628dbec8f7ffad727d9ce0143a93208a619eaf4",
+            ),
+        ),
+        (
+            "2a00000000000000000000000000003300000000000000000000000000000003",
+            "84074d7217644bae8b68fe34dbfb5dbe46575b7e6a07aa63d58fb6ca515b9a336945c43e0bef14fc88d99de173bba57d",
+            b"Rip and tear, until it's done",
+            concat!(
+                "94e90a887b1a927f501d6983395af659ff54f2d33f328adef05a885ee3359f4db7ae0a57ca3d65677ffcca02f9b9b25f",
+                "13818fed0ba3a31c0f2a8c18a0627efc4370a55efdbda12fd57ab68117a81e6b50c6c6fc2ed4842d2f32f0d782a44cf7",
+            ),
+        ),
+    ];
+
+    /// The aggregate of the three signatures above, also from blst.
+    const AGGREGATE: &str = concat!(
+        "8760d01c5310ae015ca628215a151723c5888d120d2a48aec70ecbe97d1b780c22a00d9d590527427340d69d0be941ab",
+        "092e8b05ee7915b4477437a03034e06d25d1e69ea530cb71d733199abf239bbd9dbb0a1c14c86e8e5d005fef130e197e",
+    );
+
+    #[test]
+    fn blst_vectors() {
+        let mut pks = Vec::new();
+        let mut sigs = Vec::new();
+        for (sk, pk, msg, sig) in VECTORS.iter() {
+            let mut sk_bytes = [0u8; 32];
+            sk_bytes.copy_from_slice(&from_hex(sk));
+            let sk = SecretKey::from_be_bytes(&sk_bytes).unwrap();
+            let (pk, sig) = (from_hex(pk), from_hex(sig));
+            assert_eq!(pk, sk_to_pk(&sk).to_vec());
+            assert_eq!(sig, sign(&sk, msg).to_vec());
+            assert!(verify(&pk, msg, &sig));
+            assert!(!verify(&pk, b"Other msg", &sig));
+            pks.push(pk);
+            sigs.push(sig);
+        }
+
+        let sig_refs: Vec<&[u8]> = sigs.iter().map(|sig| &sig[..]).collect();
+        let agg = aggregate(&sig_refs).unwrap();
+        assert_eq!(from_hex(AGGREGATE), agg.to_vec());
+        let pk_refs: Vec<&[u8]> = pks.iter().map(|pk| &pk[..]).collect();
+        let msgs: Vec<&[u8]> = VECTORS.iter().map(|(_, _, msg, _)| *msg).collect();
+        assert!(aggregate_verify(&pk_refs, &msgs, &agg));
+        assert!(!aggregate_verify(&pk_refs[..2], &msgs[..2], &agg));
+        assert!(!aggregate_verify(
+            &pk_refs,
+            &[msgs[1], msgs[0], msgs[2]],
+            &agg
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_inputs() {
+        let sk = SecretKey::random();
+        let pk = sk_to_pk(&sk);
+        let msg = b"Rip and tear, until it's done";
+        let sig = sign(&sk, msg);
+
+        // Signatures under another tag don't verify.
+        let other_dst = (hash_to_g2(msg, b"OTHER_DST") * sk.0)
+            .to_affine()
+            .to_compressed();
+        assert!(!verify(&pk, msg, &other_dst));
+
+        // `KeyValidate` rejects the identity, so the identity signature can't be forged for it.
+        let identity_pk = PublicKey(G1Affine::identity().into()).to_bytes();
+        assert!(key_validate(&identity_pk).is_err());
+        let identity_sig = Signature(G2Projective::identity()).to_bytes();
+        assert!(!verify(&identity_pk, msg, &identity_sig));
+        assert!(!verify(&pk, msg, &sig[..95]));
+        assert!(!verify(&pk[..47], msg, &sig));
+
+        // The basic scheme requires distinct messages.
+        let other_sk = SecretKey::random();
+        let other_sig = sign(&other_sk, msg);
+        let agg = aggregate(&[&sig[..], &other_sig[..]]).unwrap();
+        let other_pk = sk_to_pk(&other_sk);
+        assert!(!aggregate_verify(
+            &[&pk[..], &other_pk[..]],
+            &[msg, msg],
+            &agg
+        ));
+        assert!(!aggregate_verify(&[], &[], &agg));
+        assert!(aggregate(&[]).is_err());
+    }
+}
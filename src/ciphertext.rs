@@ -1,30 +1,241 @@
-use crate::util;
-use bls12_381::{pairing, G1Affine, G1Projective, G2Affine, G2Projective};
-use group::Curve;
+use crate::{util, Error, VerifiedCiphertext};
+use anyhow::{anyhow, bail};
+use bls12_381::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Scalar,
+};
+use group::{Curve, Group};
+use rand::RngCore;
 use std::cmp::Ordering;
+use std::convert::TryInto;
+use std::fmt;
 use std::hash::{Hash, Hasher};
+use subtle::Choice;
+
+/// The size of the compressed `u` component.
+const U_SIZE: usize = 48;
+
+/// The size of the compressed `w` component.
+const W_SIZE: usize = 96;
 
 /// An encrypted message.
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub struct Ciphertext(pub G1Projective, pub Vec<u8>, pub G2Projective);
+///
+/// It consists of `u = g1 * r`, the masked message `v`, and `w = hash(u, v) * r`. Use `new` and
+/// the accessors to build and inspect it: the fields are private since 0.2.0, see the changelog.
+///
+/// The compressed encodings of `u` and `w` are computed once, on construction, so that ordering,
+/// hashing and `to_bytes` don't convert the points to affine on every call. This makes sorted
+/// collections of ciphertexts, e.g. a `BTreeSet` in a mempool, much cheaper.
+#[derive(Clone)]
+pub struct Ciphertext {
+    u: G1Projective,
+    v: Vec<u8>,
+    w: G2Projective,
+    u_bytes: [u8; U_SIZE],
+    w_bytes: [u8; W_SIZE],
+}
 
 impl Ciphertext {
+    /// Creates a ciphertext from its components.
+    ///
+    /// Fails if `u` or `w` isn't a point in its subgroup. This doesn't check that the ciphertext
+    /// is valid: use `verify` for that.
+    pub fn new(u: G1Projective, v: Vec<u8>, w: G2Projective) -> anyhow::Result<Self> {
+        let u_affine = u.to_affine();
+        if !bool::from(u_affine.is_on_curve() & u_affine.is_torsion_free()) {
+            bail!("Ciphertext u is not in the G1 subgroup")
+        }
+        let w_affine = w.to_affine();
+        if !bool::from(w_affine.is_on_curve() & w_affine.is_torsion_free()) {
+            bail!("Ciphertext w is not in the G2 subgroup")
+        }
+        Ok(Ciphertext::from_parts(u, v, w))
+    }
+
+    /// Creates a ciphertext from components that are known to be in their subgroups.
+    pub(crate) fn from_parts(u: G1Projective, v: Vec<u8>, w: G2Projective) -> Self {
+        Ciphertext {
+            u_bytes: u.to_affine().to_compressed(),
+            w_bytes: w.to_affine().to_compressed(),
+            u,
+            v,
+            w,
+        }
+    }
+
+    /// Returns `u = g1 * r`, from which the decryption shares are computed.
+    pub fn u(&self) -> &G1Projective {
+        &self.u
+    }
+
+    /// Returns the message, masked with a hash of `u * sk`.
+    pub fn v(&self) -> &[u8] {
+        &self.v
+    }
+
+    /// Returns `w = hash(u, v) * r`, which proves that `u` and `v` weren't tampered with.
+    pub fn w(&self) -> &G2Projective {
+        &self.w
+    }
+
     /// Returns `true` if this is a valid ciphertext. This check is necessary to prevent
     /// chosen-ciphertext attacks.
     pub fn verify(&self) -> bool {
-        let Ciphertext(ref u, ref v, ref w) = *self;
-        let hash = util::hash_g1_g2(*u, v);
-        pairing(&G1Affine::generator(), &G2Affine::from(w))
-            == pairing(&G1Affine::from(u), &G2Affine::from(hash))
+        bool::from(self.verify_ct())
+    }
+
+    /// Returns `true` if this is a valid ciphertext for the associated data `aad`, i.e. it was
+    /// created by `PublicKey::encrypt_with_aad` with the same `aad`.
+    pub fn verify_with_aad<A: AsRef<[u8]>>(&self, aad: A) -> bool {
+        bool::from(self.verify_ct_with_aad(aad.as_ref()))
+    }
+
+    /// Returns whether this is a valid ciphertext, without branching on the result.
+    pub(crate) fn verify_ct(&self) -> Choice {
+        self.verify_ct_with_aad(&[])
+    }
+
+    fn verify_ct_with_aad(&self, aad: &[u8]) -> Choice {
+        // `e(g, w) == e(u, hash)`, with a single final exponentiation. `u` is converted to affine
+        // once, for both the hash and the pairing.
+        let (u, v, w) = (self.u(), self.v(), self.w());
+        let u = u.to_affine();
+        let hash = G2Prepared::from(util::hash_g1_affine_g2(&u, v, aad).to_affine());
+        let w = G2Prepared::from(w.to_affine());
+        multi_miller_loop(&[(&-G1Affine::generator(), &w), (&u, &hash)])
+            .final_exponentiation()
+            .is_identity()
+    }
+
+    /// Returns `true` if every ciphertext in `cts` is valid, checking them all at once.
+    ///
+    /// Each ciphertext's equation `e(g, w) == e(u, hash)` is multiplied by a random 128-bit scalar
+    /// from `rng`, and the sum is checked with a single multi-Miller loop and final
+    /// exponentiation. An invalid ciphertext makes the check fail except with negligible
+    /// probability, but it doesn't tell which one: use `invalid_in_batch` for that. An empty
+    /// batch is valid.
+    pub fn verify_batch<R: RngCore>(cts: &[Ciphertext], rng: &mut R) -> bool {
+        let mut w_sum = G2Projective::identity();
+        let mut us = Vec::with_capacity(2 * cts.len());
+        for ct in cts {
+            let r = Scalar::from_raw([rng.next_u64(), rng.next_u64() | 1, 0, 0]);
+            w_sum += ct.w * r;
+            us.push(ct.u * r);
+        }
+        // The hashes are of the original `u`s, so normalize those too.
+        us.extend(cts.iter().map(|ct| ct.u));
+        let mut us_affine = vec![G1Affine::identity(); us.len()];
+        G1Projective::batch_normalize(&us, &mut us_affine);
+        let (us_affine, orig_us) = us_affine.split_at(cts.len());
+        let hashes: Vec<G2Prepared> = cts
+            .iter()
+            .zip(orig_us)
+            .map(|(ct, u)| G2Prepared::from(util::hash_g1_affine_g2(u, &ct.v, &[]).to_affine()))
+            .collect();
+        let neg_g1 = -G1Affine::generator();
+        let w_sum = G2Prepared::from(w_sum.to_affine());
+        let mut terms: Vec<(&G1Affine, &G2Prepared)> = us_affine.iter().zip(&hashes).collect();
+        terms.push((&neg_g1, &w_sum));
+        multi_miller_loop(&terms)
+            .final_exponentiation()
+            .is_identity()
+            .into()
+    }
+
+    /// Returns the positions of the invalid ciphertexts in `cts`, in ascending order.
+    ///
+    /// This is the slow path after `verify_batch` fails: a failing batch is split in halves that
+    /// are checked again, so a few invalid ciphertexts among many are found with far fewer
+    /// pairings than verifying each one.
+    pub fn invalid_in_batch<R: RngCore>(cts: &[Ciphertext], rng: &mut R) -> Vec<usize> {
+        let mut invalid = Vec::new();
+        Ciphertext::bisect(cts, 0, rng, &mut invalid);
+        invalid
+    }
+
+    fn bisect<R: RngCore>(cts: &[Ciphertext], offset: usize, rng: &mut R, out: &mut Vec<usize>) {
+        if Ciphertext::verify_batch(cts, rng) {
+            return;
+        }
+        if cts.len() == 1 {
+            out.push(offset);
+            return;
+        }
+        let mid = cts.len() / 2;
+        Ciphertext::bisect(&cts[..mid], offset, rng, out);
+        Ciphertext::bisect(&cts[mid..], offset + mid, rng, out);
+    }
+
+    /// Returns the encoding of the ciphertext: the compressed `u` and `w`, followed by `v`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(U_SIZE + W_SIZE + self.v.len());
+        bytes.extend_from_slice(&self.u_bytes);
+        bytes.extend_from_slice(&self.w_bytes);
+        bytes.extend_from_slice(&self.v);
+        bytes
+    }
+
+    /// Decodes a ciphertext from the encoding of `to_bytes`.
+    ///
+    /// Fails if the input is too short, or `u` or `w` isn't a point in its subgroup. This doesn't
+    /// check that the ciphertext is valid: use `verify` for that.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.len() < U_SIZE + W_SIZE {
+            bail!(
+                "Invalid ciphertext length {}, expected at least {}",
+                bytes.len(),
+                U_SIZE + W_SIZE
+            )
+        }
+        let (u, rest) = bytes.split_at(U_SIZE);
+        let (w, v) = rest.split_at(W_SIZE);
+        let u_bytes: [u8; U_SIZE] = u.try_into().expect("u has the right size");
+        let w_bytes: [u8; W_SIZE] = w.try_into().expect("w has the right size");
+        let u = Option::<G1Affine>::from(G1Affine::from_compressed(&u_bytes))
+            .ok_or_else(|| anyhow!("Invalid ciphertext encoding of u"))?;
+        let w = Option::<G2Affine>::from(G2Affine::from_compressed(&w_bytes))
+            .ok_or_else(|| anyhow!("Invalid ciphertext encoding of w"))?;
+        // Decoding succeeded, so the input is the canonical encoding and can be cached as is.
+        Ok(Ciphertext {
+            u: u.into(),
+            v: v.to_vec(),
+            w: w.into(),
+            u_bytes,
+            w_bytes,
+        })
+    }
+
+    /// Verifies the ciphertext, so that it can be decrypted without checking it again.
+    pub fn into_verified(self) -> Result<VerifiedCiphertext, Error> {
+        if !self.verify() {
+            return Err(Error::InvalidCiphertext);
+        }
+        Ok(VerifiedCiphertext(self))
     }
 }
 
+impl fmt::Debug for Ciphertext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Ciphertext")
+            .field("u", &self.u)
+            .field("v", &self.v)
+            .field("w", &self.w)
+            .finish()
+    }
+}
+
+impl PartialEq for Ciphertext {
+    fn eq(&self, other: &Self) -> bool {
+        self.u_bytes == other.u_bytes && self.w_bytes == other.w_bytes && self.v == other.v
+    }
+}
+
+impl Eq for Ciphertext {}
+
 impl Hash for Ciphertext {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        let Ciphertext(ref u, ref v, ref w) = *self;
-        u.to_affine().to_compressed().as_ref().hash(state);
-        v.hash(state);
-        w.to_affine().to_compressed().as_ref().hash(state);
+        self.u_bytes.as_ref().hash(state);
+        self.v.hash(state);
+        self.w_bytes.as_ref().hash(state);
     }
 }
 
@@ -36,10 +247,83 @@ impl PartialOrd for Ciphertext {
 
 impl Ord for Ciphertext {
     fn cmp(&self, other: &Self) -> Ordering {
-        let Ciphertext(ref u0, ref v0, ref w0) = self;
-        let Ciphertext(ref u1, ref v1, ref w1) = other;
-        util::cmp_g1_projective(u0, u1)
-            .then(v0.cmp(v1))
-            .then(util::cmp_g2_projective(w0, w1))
+        self.u_bytes
+            .cmp(&other.u_bytes)
+            .then(self.v.cmp(&other.v))
+            .then(self.w_bytes.cmp(&other.w_bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretKey;
+
+    #[test]
+    fn cached_encodings() {
+        let pk = SecretKey::random().public_key();
+        let cts: Vec<_> = (0..8u8).map(|i| pk.encrypt([i])).collect();
+        for a in &cts {
+            let decoded = Ciphertext::from_bytes(&a.to_bytes()).unwrap();
+            assert_eq!(*a, decoded);
+            assert_eq!(a.to_bytes(), decoded.to_bytes());
+            for b in &cts {
+                // The order is that of the compressed `u`, then `v`, then the compressed `w`.
+                let expected = util::cmp_g1_projective(a.u(), b.u())
+                    .then(a.v().cmp(b.v()))
+                    .then(util::cmp_g2_projective(a.w(), b.w()));
+                assert_eq!(expected, a.cmp(b));
+                assert_eq!(a == b, a.cmp(b) == Ordering::Equal);
+            }
+        }
+    }
+
+    #[test]
+    fn new_validates_points() {
+        let sk = SecretKey::random();
+        let ct = sk.public_key().encrypt(b"Rip and tear, until it's done");
+        let rebuilt = Ciphertext::new(*ct.u(), ct.v().to_vec(), *ct.w()).unwrap();
+        assert_eq!(ct, rebuilt);
+        assert!(rebuilt.verify());
+        assert_eq!(
+            Ok(b"Rip and tear, until it's done".to_vec()),
+            sk.try_decrypt(&rebuilt)
+        );
+
+        // Points on the curves with `x = 4` and `x = 2`, outside the prime-order subgroups.
+        let mut u_bytes = [0u8; U_SIZE];
+        u_bytes[0] = 0x80;
+        u_bytes[U_SIZE - 1] = 4;
+        let bad_u = G1Affine::from_compressed_unchecked(&u_bytes).unwrap();
+        assert!(!bool::from(bad_u.is_torsion_free()));
+        let mut w_bytes = [0u8; W_SIZE];
+        w_bytes[0] = 0x80;
+        w_bytes[W_SIZE - 1] = 2;
+        let bad_w = G2Affine::from_compressed_unchecked(&w_bytes).unwrap();
+        assert!(!bool::from(bad_w.is_torsion_free()));
+
+        assert!(Ciphertext::new(bad_u.into(), ct.v().to_vec(), *ct.w()).is_err());
+        assert!(Ciphertext::new(*ct.u(), ct.v().to_vec(), bad_w.into()).is_err());
+    }
+
+    #[test]
+    fn verify_batch() {
+        let mut rng = rand::thread_rng();
+        let pk = SecretKey::random().public_key();
+        let mut cts: Vec<_> = (0..50u8).map(|i| pk.encrypt([i])).collect();
+        assert!(Ciphertext::verify_batch(&cts, &mut rng));
+        assert!(Ciphertext::invalid_in_batch(&cts, &mut rng).is_empty());
+        assert!(Ciphertext::verify_batch(&[], &mut rng));
+
+        let ct = &cts[17];
+        cts[17] =
+            Ciphertext::from_parts(*ct.u(), ct.v().to_vec(), ct.w() + G2Projective::generator());
+        assert!(!cts[17].verify());
+        assert!(!Ciphertext::verify_batch(&cts, &mut rng));
+        assert_eq!(vec![17], Ciphertext::invalid_in_batch(&cts, &mut rng));
+
+        let ct = &cts[42];
+        cts[42] = Ciphertext::from_parts(*ct.u(), ct.v().to_vec(), -ct.w());
+        assert_eq!(vec![17, 42], Ciphertext::invalid_in_batch(&cts, &mut rng));
     }
 }
@@ -0,0 +1,78 @@
+use crate::SignatureShare;
+use serde::{Deserialize, Serialize};
+
+/// The domain tag of coin messages, so that they can't collide with other signed messages.
+const COIN_TAG: &[u8] = b"rust-tc-coin-v1";
+
+/// A signature share of a common coin, see `SecretKeyShare::coin_share`.
+///
+/// Any `t + 1` valid shares for the same instance and round combine into the same coin value, and
+/// no `t` signers can predict it before an honest one has released their share.
+#[derive(Clone, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
+pub struct CoinShare(pub SignatureShare);
+
+/// Returns the message signed for the coin of the given instance and round: the tag, the instance
+/// ID with a `u64` big-endian length prefix, and the round as a big-endian `u64`.
+pub(crate) fn coin_message(instance: &[u8], round: u64) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(COIN_TAG.len() + instance.len() + 16);
+    msg.extend_from_slice(COIN_TAG);
+    msg.extend_from_slice(&(instance.len() as u64).to_be_bytes());
+    msg.extend_from_slice(instance);
+    msg.extend_from_slice(&round.to_be_bytes());
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretKeySet;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn combine_subsets() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let instance = b"Rip and tear, until it's done";
+        let shares: BTreeMap<_, _> = (0..5)
+            .map(|i| (i, sk_set.secret_key_share(i).coin_share(instance, 7)))
+            .collect();
+        for (i, share) in &shares {
+            assert!(pk_set.verify_coin_share(*i, share, instance, 7));
+            assert!(!pk_set.verify_coin_share(*i, share, instance, 8));
+            assert!(!pk_set.verify_coin_share(*i, share, b"Other instance", 7));
+            assert!(!pk_set.verify_coin_share(*i + 1, share, instance, 7));
+        }
+
+        let coin = pk_set.combine_coin(&shares, instance, 7).unwrap();
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let subset: Vec<_> = [a, b, c].iter().map(|i| (*i, &shares[i])).collect();
+                    assert_eq!(coin, pk_set.combine_coin(subset, instance, 7).unwrap());
+                }
+            }
+        }
+
+        let next: BTreeMap<_, _> = (0..3)
+            .map(|i| (i, sk_set.secret_key_share(i).coin_share(instance, 8)))
+            .collect();
+        assert_ne!(coin, pk_set.combine_coin(&next, instance, 8).unwrap());
+        assert!(pk_set.combine_coin(&shares, instance, 8).is_err());
+
+        // A single share for the wrong round spoils the combination.
+        let mut mixed = shares.clone();
+        mixed.insert(1, next[&1].clone());
+        assert!(pk_set.combine_coin(&mixed, instance, 7).is_err());
+        assert!(pk_set
+            .combine_coin(shares.iter().take(2), instance, 7)
+            .is_err());
+    }
+
+    #[test]
+    fn coin_message_is_unambiguous() {
+        assert_ne!(coin_message(b"ab", 1), coin_message(b"a", 1));
+        assert_ne!(coin_message(b"", 1), coin_message(b"", 2));
+        assert_ne!(coin_message(&[0; 8], 0), coin_message(&[], 0));
+    }
+}
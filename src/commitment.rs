@@ -1,11 +1,17 @@
-use crate::util::cmp_g1_projective;
+use crate::util::{cmp_g1_projective, hash_to_scalar, sha3_256};
 use crate::{IntoScalar, PublicKey};
-use bls12_381::{G1Affine, G1Projective};
-use group::Curve;
+use anyhow::{anyhow, bail};
+use bls12_381::{multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective};
+use group::{Curve, Group};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::borrow::Borrow;
 use std::cmp;
+use std::convert::TryInto;
+use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::ops::{Add, AddAssign};
+use std::slice;
 use subtle::Choice;
 
 /// A commitment to a univariate polynomial.
@@ -15,6 +21,15 @@ pub struct Commitment {
     pub coeff: Vec<G1Projective>,
 }
 
+impl<'a> IntoIterator for &'a Commitment {
+    type Item = &'a G1Projective;
+    type IntoIter = slice::Iter<'a, G1Projective>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl PartialOrd for Commitment {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         Some(self.cmp(&other))
@@ -42,6 +57,54 @@ impl Hash for Commitment {
     }
 }
 
+/// The size of a compressed coefficient.
+const COEFF_SIZE: usize = 48;
+
+/// The domain separation tag of the Fiat-Shamir weights in `pairs_with_g2`.
+const FIAT_SHAMIR_DST: &[u8] = b"RUST_TC_PAIRS_WITH_G2_V1_";
+
+impl Serialize for Commitment {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.to_bytes())
+    }
+}
+
+struct CommitmentVisitor;
+
+impl<'de> Visitor<'de> for CommitmentVisitor {
+    type Value = Commitment;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a sequence of compressed G1 points of {} bytes each",
+            COEFF_SIZE
+        )
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.len() % COEFF_SIZE != 0 {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        Commitment::from_bytes(v).map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Commitment {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(CommitmentVisitor)
+    }
+}
+
 impl<B: Borrow<Commitment>> AddAssign<B> for Commitment {
     fn add_assign(&mut self, rhs: B) {
         let len = cmp::max(self.coeff.len(), rhs.borrow().coeff.len());
@@ -73,15 +136,104 @@ impl<B: Borrow<Commitment>> Add<B> for Commitment {
 }
 
 impl Commitment {
-    /// Returns the polynomial's degree.
+    /// Returns the polynomial's degree, or `0` if the commitment is empty.
     pub fn degree(&self) -> usize {
-        self.coeff.len() - 1
+        self.coeff.len().saturating_sub(1)
     }
 
-    /// Returns the `i`-th public key share.
+    /// Returns an iterator over the coefficients, starting with the constant term.
+    pub fn iter(&self) -> slice::Iter<'_, G1Projective> {
+        self.coeff.iter()
+    }
+
+    /// Returns the concatenated compressed coefficients.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.coeff.len() * COEFF_SIZE);
+        for c in &self.coeff {
+            bytes.extend_from_slice(&c.to_affine().to_compressed());
+        }
+        bytes
+    }
+
+    /// Decodes a commitment from the encoding of `to_bytes`.
+    ///
+    /// Fails if the length isn't a multiple of the point size, or a coefficient isn't a point in
+    /// the G1 subgroup. Trailing identity coefficients are removed.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.len() % COEFF_SIZE != 0 {
+            bail!(
+                "Invalid commitment length {}, expected a multiple of {}",
+                bytes.len(),
+                COEFF_SIZE
+            )
+        }
+        let mut coeff = Vec::with_capacity(bytes.len() / COEFF_SIZE);
+        for chunk in bytes.chunks(COEFF_SIZE) {
+            let chunk: &[u8; COEFF_SIZE] = chunk.try_into().expect("chunk has the right size");
+            let point: Option<G1Affine> = G1Affine::from_compressed(chunk).into();
+            let point = point.ok_or_else(|| anyhow!("Invalid commitment coefficient"))?;
+            coeff.push(G1Projective::from(point));
+        }
+        let mut commit = Commitment { coeff };
+        // Trailing identity coefficients would inflate the degree, and with it the threshold.
+        commit.remove_zeros();
+        Ok(commit)
+    }
+
+    /// Returns `true` if `other` is a commitment in G2 to the same polynomial, i.e. its `i`-th
+    /// coefficient has the same discrete logarithm as ours.
+    ///
+    /// Points in G1 can't be multiplied with each other, but a pairing relates them to points in
+    /// G2: the check is `e(c_i, g2) == e(g1, d_i)` for all `i`. The equations are combined with
+    /// weights derived from a hash of both commitments into a single multi-Miller loop. This lets
+    /// a DKG node check a row commitment received in G2 against the row of the dealer's
+    /// `BivarCommitment`, without knowing the row polynomial.
+    pub fn pairs_with_g2(&self, other: &[G2Projective]) -> bool {
+        if self.coeff.len() != other.len() {
+            return false;
+        }
+        let mut transcript = self.to_bytes();
+        for d in other {
+            transcript.extend_from_slice(&d.to_affine().to_compressed());
+        }
+        let seed = sha3_256(&transcript);
+        let mut lhs = G1Projective::identity();
+        let mut rhs = G2Projective::identity();
+        for (i, (c, d)) in self.coeff.iter().zip(other).enumerate() {
+            let mut input = seed.to_vec();
+            input.extend_from_slice(&(i as u64).to_be_bytes());
+            let weight = hash_to_scalar(&input, FIAT_SHAMIR_DST);
+            lhs += c * weight;
+            rhs += d * weight;
+        }
+        let g2 = G2Prepared::from(G2Affine::generator());
+        let rhs = G2Prepared::from(rhs.to_affine());
+        let gt = multi_miller_loop(&[(&lhs.to_affine(), &g2), (&-G1Affine::generator(), &rhs)])
+            .final_exponentiation();
+        bool::from(gt.is_identity())
+    }
+
+    /// Returns the indices of the coefficients that differ between `self` and `other`, in
+    /// ascending order.
+    ///
+    /// A missing coefficient counts as the identity, like a zero coefficient of a polynomial. In
+    /// a DKG complaint this localizes the term a cheating dealer modified.
+    pub fn diff_positions(&self, other: &Commitment) -> Vec<usize> {
+        let identity = G1Projective::identity();
+        let len = self.coeff.len().max(other.coeff.len());
+        (0..len)
+            .filter(|&i| {
+                let a = self.coeff.get(i).unwrap_or(&identity);
+                let b = other.coeff.get(i).unwrap_or(&identity);
+                a != b
+            })
+            .collect()
+    }
+
+    /// Returns the `i`-th public key share, or the identity if the commitment is empty.
     pub fn evaluate<T: IntoScalar>(&self, i: T) -> G1Projective {
         let result = match self.coeff.last() {
-            None => return G1Projective::generator(),
+            None => return G1Projective::identity(),
             Some(c) => *c,
         };
         let x = i.into_scalar();
@@ -93,8 +245,44 @@ impl Commitment {
         res
     }
 
+    /// Returns the values of the polynomial at `1, 2, ..., n`.
+    ///
+    /// The first `t + 1` values are computed with Horner's method, multiplying by the small
+    /// evaluation points. The rest follow from the table of finite differences, which costs `t`
+    /// group additions per value instead of `t` scalar multiplications. The points are public, so
+    /// the multiplications don't need to be constant time.
+    pub(crate) fn evaluate_range(&self, n: usize) -> Vec<G1Projective> {
+        let degree = match self.coeff.len() {
+            0 => return vec![G1Projective::identity(); n],
+            len => len - 1,
+        };
+        let horner = |x: u64| {
+            let mut res = G1Projective::identity();
+            for c in self.coeff.iter().rev() {
+                res = mul_u64(&res, x) + c;
+            }
+            res
+        };
+        // `diffs[j]` is the `j`-th forward difference at the current point.
+        let mut diffs: Vec<G1Projective> = (1..=degree.min(n) as u64 + 1).map(horner).collect();
+        for j in 1..diffs.len() {
+            for k in (j..diffs.len()).rev() {
+                diffs[k] = diffs[k] - diffs[k - 1];
+            }
+        }
+        let mut values = Vec::with_capacity(n);
+        for _ in 0..n {
+            values.push(diffs[0]);
+            for j in 0..diffs.len() - 1 {
+                let next = diffs[j + 1];
+                diffs[j] += next;
+            }
+        }
+        values
+    }
+
     /// Removes all trailing zero coefficients.
-    fn remove_zeros(&mut self) {
+    pub(crate) fn remove_zeros(&mut self) {
         let zeros = self
             .coeff
             .iter()
@@ -105,22 +293,35 @@ impl Commitment {
         self.coeff.truncate(len)
     }
 
-    /// Generates a public key from a commitment
-    pub fn public_key(&self) -> PublicKey {
-        let mut pub_key = G1Projective::from(self.coeff[0]);
+    /// Generates a public key from a commitment, or returns `None` if the commitment is empty.
+    pub fn public_key(&self) -> Option<PublicKey> {
+        let mut pub_key = G1Projective::from(*self.coeff.first()?);
         let length = self.coeff.len() as usize;
         for i in 1..length {
             pub_key += G1Projective::from(self.coeff[i]);
         }
-        PublicKey(pub_key)
+        Some(PublicKey(pub_key))
+    }
+}
+
+/// Returns `p * x` by double-and-add. This is not constant time in `x`.
+fn mul_u64(p: &G1Projective, x: u64) -> G1Projective {
+    let mut res = G1Projective::identity();
+    for i in (0..64 - x.leading_zeros()).rev() {
+        res = res.double();
+        if (x >> i) & 1 == 1 {
+            res += p;
+        }
     }
+    res
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use crate::{Poly, PublicKeySet, SecretKeySet};
+    use crate::{BivarPoly, Poly, PublicKeySet, SecretKeySet};
+    use bls12_381::Scalar;
 
     #[test]
     fn basic() {
@@ -137,4 +338,118 @@ mod tests {
 
         assert_eq!(pks, sks.public_keys())
     }
+
+    #[test]
+    fn deserialize_removes_zeros() {
+        let mut commit = Poly::monomial(2).commitment();
+        let expected = commit.clone();
+        commit.coeff.extend(vec![G1Projective::identity(); 3]);
+        assert_eq!(5, commit.degree());
+
+        let bytes = bincode::serialize(&commit).expect("failed to serialize");
+        let decoded: Commitment = bincode::deserialize(&bytes).expect("failed to deserialize");
+        assert_eq!(2, decoded.degree());
+        assert_eq!(expected, decoded);
+        assert_eq!(PublicKeySet::from(expected), PublicKeySet::from(decoded));
+
+        let bytes = bincode::serialize(&Poly::monomial(1).commitment()).unwrap();
+        assert!(bincode::deserialize::<Commitment>(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn pairs_with_g2() {
+        let mut rng = rand::thread_rng();
+        let bi_poly = BivarPoly::random(3);
+        let bi_commit = bi_poly.commitment();
+        for m in 1..=4 {
+            // The dealer sends the row in G2; the node checks it against the bivariate commitment.
+            let row_poly = bi_poly.row(m);
+            let row_g2 = SecretKeySet::from(row_poly.clone()).public_keys_g2().commit;
+            let row_commit = bi_commit.row(m);
+            assert_eq!(row_poly.commitment(), row_commit);
+            assert!(row_commit.pairs_with_g2(&row_g2));
+
+            // A cheating dealer who modified the polynomial would be detected.
+            let wrong_poly = row_poly + Poly::monomial(2) * Poly::constant(Scalar::from(5));
+            let wrong_g2 = SecretKeySet::from(wrong_poly).public_keys_g2().commit;
+            assert!(!row_commit.pairs_with_g2(&wrong_g2));
+            assert!(!row_commit.pairs_with_g2(&row_g2[..3]));
+        }
+
+        let other_g2 = SecretKeySet::random(3, &mut rng).public_keys_g2().commit;
+        assert!(!bi_commit.row(1).pairs_with_g2(&other_g2));
+        assert!(Commitment { coeff: vec![] }.pairs_with_g2(&[]));
+    }
+
+    #[test]
+    fn diff_positions() {
+        let bi_poly = BivarPoly::random(3);
+        let bi_commit = bi_poly.commitment();
+        for m in 1..=4 {
+            let row_poly = bi_poly.row(m);
+            let row_commit = bi_commit.row(m);
+            assert!(row_commit.diff_positions(&row_poly.commitment()).is_empty());
+
+            // A cheating dealer adds `5 X²` to the row: only the coefficient of `X²` differs.
+            let wrong_poly = row_poly + Poly::monomial(2) * Poly::constant(Scalar::from(5));
+            assert_eq!(vec![2], row_commit.diff_positions(&wrong_poly.commitment()));
+        }
+
+        // Extra coefficients differ unless they are the identity.
+        let poly = Poly::monomial(1);
+        let commit = poly.commitment();
+        let mut longer = commit.clone();
+        longer.coeff.push(G1Projective::identity());
+        assert!(commit.diff_positions(&longer).is_empty());
+        let extended = (poly + Poly::monomial(3)).commitment();
+        assert_eq!(vec![3], commit.diff_positions(&extended));
+        assert_eq!(vec![3], extended.diff_positions(&commit));
+    }
+
+    #[test]
+    fn evaluate_range() {
+        let mut rng = rand::thread_rng();
+        for degree in 0..5 {
+            let commit = Poly::random(degree, &mut rng).commitment();
+            for n in [0, 1, degree, degree + 1, 20] {
+                let expected: Vec<_> = (1..=n).map(|i| commit.evaluate(i)).collect();
+                assert_eq!(expected, commit.evaluate_range(n));
+            }
+        }
+        let empty = Commitment { coeff: vec![] };
+        assert_eq!(vec![G1Projective::identity(); 3], empty.evaluate_range(3));
+    }
+
+    #[test]
+    fn iter() {
+        let poly = Poly::random(3, &mut rand::thread_rng());
+        let commit = poly.commitment();
+        assert_eq!(3, commit.degree());
+        assert_eq!(commit.degree() + 1, commit.iter().count());
+        for (c, coeff) in commit.iter().zip(&poly.coeff) {
+            assert_eq!(G1Projective::generator() * coeff, *c);
+        }
+        let mut count = 0;
+        for c in &commit {
+            assert!(!bool::from(c.is_identity()));
+            count += 1;
+        }
+        assert_eq!(4, count);
+    }
+
+    #[test]
+    fn empty() {
+        let commit = Commitment { coeff: vec![] };
+        assert_eq!(0, commit.degree());
+        assert_eq!(None, commit.public_key());
+        // Like the zero polynomial, which evaluates to zero everywhere.
+        assert_eq!(Scalar::zero(), Poly::zero().evaluate(5));
+        assert_eq!(G1Projective::identity(), commit.evaluate(0));
+        assert_eq!(G1Projective::identity(), commit.evaluate(5));
+        assert_eq!(Poly::zero().commitment(), commit);
+
+        let commit = Poly::monomial(2).commitment();
+        assert_eq!(2, commit.degree());
+        assert!(commit.public_key().is_some());
+    }
 }
@@ -0,0 +1,213 @@
+use crate::{Ciphertext, DecryptionShare, PublicKeySet};
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+
+/// The state of a `DecryptionSession` after a share was added.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SessionProgress {
+    /// More shares are needed to decrypt.
+    Pending {
+        /// The number of valid shares received so far.
+        received: usize,
+        /// The number of further shares needed.
+        missing: usize,
+    },
+    /// Enough valid shares were received, and this is the decrypted message.
+    Complete(Vec<u8>),
+}
+
+/// Collects the decryption shares of one ciphertext as they arrive, and decrypts it as soon as
+/// there are enough.
+///
+/// The ciphertext is verified once, when the session is created, and every share when it is
+/// added: a share that fails is reported and discarded, and the session carries on with the
+/// others.
+#[derive(Clone, Debug)]
+pub struct DecryptionSession {
+    pk_set: PublicKeySet,
+    ct: Ciphertext,
+    shares: BTreeMap<usize, DecryptionShare>,
+}
+
+impl DecryptionSession {
+    /// Starts collecting decryption shares of `ct` for the key set `pk_set`.
+    ///
+    /// Fails if the ciphertext isn't valid, since no shares of it could be verified.
+    pub fn new(pk_set: &PublicKeySet, ct: Ciphertext) -> Result<Self> {
+        if !ct.verify() {
+            bail!("Cannot decrypt an invalid ciphertext")
+        }
+        Ok(DecryptionSession {
+            pk_set: pk_set.clone(),
+            ct,
+            shares: BTreeMap::new(),
+        })
+    }
+
+    /// Adds the decryption share of the participant with index `i`.
+    ///
+    /// Fails, leaving the session unchanged, if a share with index `i` was already added or if
+    /// the share isn't `i`'s share of the ciphertext. Otherwise returns `Complete` with the message
+    /// if there are now at least `t + 1` shares, and `Pending` if not. Shares added after
+    /// completion are still checked, and give the same message.
+    pub fn add_share(&mut self, i: usize, share: DecryptionShare) -> Result<SessionProgress> {
+        if self.shares.contains_key(&i) {
+            bail!("Duplicate decryption share {}", i)
+        }
+        if !self
+            .pk_set
+            .public_key_share(i)
+            .verify_decryption_share(&share, &self.ct)
+        {
+            bail!("Invalid decryption share {}", i)
+        }
+        self.shares.insert(i, share);
+        self.progress()
+    }
+
+    /// Returns the number of valid shares added so far.
+    pub fn len(&self) -> usize {
+        self.shares.len()
+    }
+
+    /// Returns `true` if no shares have been added.
+    pub fn is_empty(&self) -> bool {
+        self.shares.is_empty()
+    }
+
+    /// Returns `true` if there are enough shares to decrypt.
+    pub fn is_complete(&self) -> bool {
+        self.pk_set.has_quorum(self.shares.len())
+    }
+
+    /// Returns the ciphertext being decrypted.
+    pub fn ciphertext(&self) -> &Ciphertext {
+        &self.ct
+    }
+
+    /// Returns the current state of the session: the message if there are enough shares, and the
+    /// number of missing shares otherwise.
+    pub fn progress(&self) -> Result<SessionProgress> {
+        if !self.is_complete() {
+            return Ok(SessionProgress::Pending {
+                received: self.shares.len(),
+                missing: self.pk_set.missing(self.shares.len()),
+            });
+        }
+        let msg = self.pk_set.decrypt(&self.shares, &self.ct)?;
+        Ok(SessionProgress::Complete(msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretKeySet;
+
+    #[test]
+    fn out_of_order() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+        let ct = pk_set.public_key().unwrap().encrypt(msg);
+        let share = |i: usize| sk_set.secret_key_share(i).try_decrypt_share(&ct).unwrap();
+
+        let mut session = DecryptionSession::new(&pk_set, ct.clone()).unwrap();
+        assert!(session.is_empty());
+        assert_eq!(
+            SessionProgress::Pending {
+                received: 1,
+                missing: 2
+            },
+            session.add_share(7, share(7)).unwrap()
+        );
+        assert_eq!(
+            SessionProgress::Pending {
+                received: 2,
+                missing: 1
+            },
+            session.add_share(2, share(2)).unwrap()
+        );
+        assert!(!session.is_complete());
+        assert_eq!(
+            SessionProgress::Complete(msg.to_vec()),
+            session.add_share(4, share(4)).unwrap()
+        );
+        assert!(session.is_complete());
+        assert_eq!(
+            SessionProgress::Complete(msg.to_vec()),
+            session.add_share(0, share(0)).unwrap()
+        );
+        assert_eq!(4, session.len());
+    }
+
+    #[test]
+    fn rejects_duplicates_and_invalid_shares() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(1, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+        let ct = pk_set.public_key().unwrap().encrypt(msg);
+        let share = |i: usize| sk_set.secret_key_share(i).try_decrypt_share(&ct).unwrap();
+
+        let mut session = DecryptionSession::new(&pk_set, ct.clone()).unwrap();
+        session.add_share(3, share(3)).unwrap();
+        // A duplicate index is rejected, even with a different share.
+        assert!(session.add_share(3, share(3)).is_err());
+        assert!(session.add_share(3, share(5)).is_err());
+        // Participant 5's share under index 6, and a share of another ciphertext.
+        assert!(session.add_share(6, share(5)).is_err());
+        let other_ct = pk_set.public_key().unwrap().encrypt(msg);
+        let other_share = sk_set
+            .secret_key_share(6)
+            .try_decrypt_share(&other_ct)
+            .unwrap();
+        assert!(session.add_share(6, other_share).is_err());
+
+        // The rejected shares didn't poison the session.
+        assert_eq!(1, session.len());
+        assert_eq!(
+            SessionProgress::Complete(msg.to_vec()),
+            session.add_share(6, share(6)).unwrap()
+        );
+    }
+
+    #[test]
+    fn insufficient_shares() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(3, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let ct = pk_set
+            .public_key()
+            .unwrap()
+            .encrypt(b"Rip and tear, until it's done");
+
+        let mut session = DecryptionSession::new(&pk_set, ct.clone()).unwrap();
+        assert_eq!(
+            SessionProgress::Pending {
+                received: 0,
+                missing: 4
+            },
+            session.progress().unwrap()
+        );
+        for i in 0..3 {
+            let share = sk_set.secret_key_share(i).try_decrypt_share(&ct).unwrap();
+            session.add_share(i, share).unwrap();
+        }
+        assert!(!session.is_complete());
+        assert_eq!(
+            SessionProgress::Pending {
+                received: 3,
+                missing: 1
+            },
+            session.progress().unwrap()
+        );
+
+        // Invalid ciphertexts are rejected up front.
+        let mut v = ct.v().to_vec();
+        v[0] ^= 1;
+        let invalid = Ciphertext::from_parts(*ct.u(), v, *ct.w());
+        assert!(DecryptionSession::new(&pk_set, invalid).is_err());
+    }
+}
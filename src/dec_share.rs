@@ -1,4 +1,158 @@
-use bls12_381::G1Projective;
+use crate::util::cmp_g1_projective;
+use crate::Error;
+use anyhow::bail;
+use bls12_381::{G1Affine, G1Projective};
+use group::Curve;
+use std::cmp::Ordering;
+use std::convert::TryInto;
+use std::hash::{Hash, Hasher};
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+/// The size of a compressed decryption share.
+const SHARE_SIZE: usize = 48;
+
+/// A participant's share of the decryption of a ciphertext. It is hashed and ordered by its
+/// compressed encoding.
+///
+/// Shares received from others should be built with `try_new`, which rejects points that would
+/// make `PublicKeySet::decrypt` fail or return the wrong plaintext.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct DecryptionShare(pub G1Projective);
+
+impl Hash for DecryptionShare {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_bytes().as_ref().hash(state);
+    }
+}
+
+impl PartialOrd for DecryptionShare {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DecryptionShare {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_g1_projective(&self.0, &other.0)
+    }
+}
+
+impl DecryptionShare {
+    /// Returns the share with the given point, or `Error::InvalidShare` if it is the identity or
+    /// not a point in the G1 subgroup.
+    pub fn try_new(point: G1Affine) -> Result<Self, Error> {
+        let share = DecryptionShare(point.into());
+        if !share.is_valid() {
+            return Err(Error::InvalidShare);
+        }
+        Ok(share)
+    }
+
+    /// Returns the share with the given point, without checking it.
+    ///
+    /// The point must come from a trusted source, e.g. a share this process computed itself.
+    pub fn new_unchecked(point: G1Projective) -> Self {
+        DecryptionShare(point)
+    }
+
+    /// Returns the compressed encoding of the share.
+    pub fn to_bytes(&self) -> [u8; SHARE_SIZE] {
+        self.0.to_affine().to_compressed()
+    }
+
+    /// Decodes a share from its compressed encoding.
+    ///
+    /// Fails if the length is wrong, or if the bytes aren't a point `try_new` accepts.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let bytes: &[u8; SHARE_SIZE] = match bytes.try_into() {
+            Ok(bytes) => bytes,
+            Err(_) => bail!(
+                "Invalid decryption share length {}, expected {}",
+                bytes.len(),
+                SHARE_SIZE
+            ),
+        };
+        match Option::<G1Affine>::from(G1Affine::from_compressed(bytes)) {
+            Some(affine) => Ok(DecryptionShare::try_new(affine)?),
+            None => bail!("Invalid decryption share encoding"),
+        }
+    }
+
+    /// Returns `true` if the share is a point in the G1 subgroup other than the identity.
+    ///
+    /// This is a cheap sanity check for shares received from the network; it doesn't show that
+    /// the share belongs to a particular ciphertext or key.
+    pub fn is_valid(&self) -> bool {
+        let p: G1Affine = self.0.to_affine();
+        !bool::from(p.is_identity()) && bool::from(p.is_on_curve() & p.is_torsion_free())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretKeySet;
+    use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+    #[test]
+    fn hash_and_order() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let ct = sk_set
+            .public_keys()
+            .public_key()
+            .unwrap()
+            .encrypt(b"Rip and tear, until it's done");
+        let shares: Vec<DecryptionShare> = (0..6)
+            .map(|i| sk_set.secret_key_share(i).try_decrypt_share(&ct).unwrap())
+            .collect();
+
+        let set: HashSet<DecryptionShare> = shares.iter().chain(&shares).copied().collect();
+        assert_eq!(6, set.len());
+
+        let sorted: BTreeSet<DecryptionShare> = shares.iter().copied().collect();
+        let decoded: BTreeSet<DecryptionShare> = shares
+            .iter()
+            .map(|share| DecryptionShare::from_bytes(&share.to_bytes()).unwrap())
+            .collect();
+        assert!(sorted.iter().eq(decoded.iter()));
+        let encodings: Vec<_> = sorted.iter().map(DecryptionShare::to_bytes).collect();
+        assert!(encodings.windows(2).all(|w| w[0] < w[1]));
+
+        assert!(DecryptionShare::from_bytes(&shares[0].to_bytes()[1..]).is_err());
+        let identity = DecryptionShare::new_unchecked(G1Projective::identity());
+        assert!(DecryptionShare::from_bytes(&identity.to_bytes()).is_err());
+    }
+
+    #[test]
+    fn try_new() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(1, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+        let ct = pk_set.public_key().unwrap().encrypt(msg);
+        let share = sk_set.secret_key_share(0).try_decrypt_share(&ct).unwrap();
+        assert_eq!(Ok(share), DecryptionShare::try_new(share.0.to_affine()));
+
+        assert_eq!(
+            Err(Error::InvalidShare),
+            DecryptionShare::try_new(G1Affine::identity())
+        );
+        // A point on the curve with `x = 4`, outside the prime-order subgroup.
+        let mut bytes = [0u8; 48];
+        bytes[0] = 0x80;
+        bytes[47] = 4;
+        let point = G1Affine::from_compressed_unchecked(&bytes).unwrap();
+        assert_eq!(Err(Error::InvalidShare), DecryptionShare::try_new(point));
+
+        // An unchecked identity share makes decryption fail with a typed error instead of
+        // returning the wrong plaintext.
+        let mut shares = BTreeMap::new();
+        shares.insert(0, share);
+        shares.insert(1, DecryptionShare::new_unchecked(G1Projective::identity()));
+        let err = pk_set.decrypt(&shares, &ct).unwrap_err();
+        assert_eq!(
+            Some(&Error::InvalidDecryptionShare(1)),
+            err.downcast_ref::<Error>()
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+/// Errors that callers may want to tell apart.
+///
+/// Most fallible functions return `anyhow::Result`; these errors can be recovered from it with
+/// `downcast_ref::<Error>()`. A few, like `SecretKey::try_decrypt`, return them directly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The polynomial degree is too high for its coefficients to be stored.
+    DegreeOverflow,
+    /// The ciphertext failed verification.
+    InvalidCiphertext,
+    /// There were no signatures to aggregate.
+    EmptyAggregate,
+    /// The signature or signature share at this position is the identity or not a point in the G2
+    /// subgroup.
+    InvalidSignature(usize),
+    /// The output buffer is too small: the plaintext has this many bytes.
+    BufferTooSmall(usize),
+    /// The decryption share at this position is the identity or not a point in the G1 subgroup.
+    InvalidDecryptionShare(usize),
+    /// A single share, e.g. one passed to `DecryptionShare::try_new`, is the identity or not a
+    /// point in its subgroup.
+    InvalidShare,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::DegreeOverflow => write!(f, "polynomial degree too high"),
+            Error::InvalidCiphertext => write!(f, "invalid ciphertext"),
+            Error::EmptyAggregate => write!(f, "no signatures to aggregate"),
+            Error::InvalidSignature(i) => write!(f, "invalid signature at position {}", i),
+            Error::BufferTooSmall(len) => write!(f, "output buffer too small, need {} bytes", len),
+            Error::InvalidDecryptionShare(i) => {
+                write!(f, "invalid decryption share at position {}", i)
+            }
+            Error::InvalidShare => write!(f, "invalid share"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,67 @@
+//! Constants and helpers for the scalar field of BLS12-381, e.g. to build FFT domains.
+
+use bls12_381::Scalar;
+use ff::PrimeField;
+
+/// The field modulus `r`, which is also the order of the groups, in little-endian byte order like
+/// `Scalar::to_bytes`.
+pub const MODULUS_BYTES: [u8; 32] = [
+    0x01, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0x02, 0xa4, 0xbd, 0x53,
+    0x05, 0xd8, 0xa1, 0x09, 0x08, 0xd8, 0x39, 0x33, 0x48, 0x7d, 0x9d, 0x29, 0x53, 0xa7, 0xed, 0x73,
+];
+
+/// The 2-adicity of the field: `2^S` is the largest power of two that divides `r - 1`, so
+/// evaluation domains of up to `2^S` points have a root of unity.
+pub const S: u32 = <Scalar as PrimeField>::S;
+
+/// Returns a primitive `2^log_n`-th root of unity, i.e. an element of order exactly `2^log_n`.
+///
+/// # Panics
+///
+/// Panics if `log_n` is greater than `S`.
+pub fn root_of_unity(log_n: u32) -> Scalar {
+    assert!(log_n <= S, "no root of unity of order 2^{}", log_n);
+    let mut root = Scalar::root_of_unity();
+    for _ in log_n..S {
+        root = root.square();
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff::Field;
+
+    #[test]
+    fn modulus() {
+        assert!(bool::from(Scalar::from_bytes(&MODULUS_BYTES).is_none()));
+        let mut r_minus_1 = MODULUS_BYTES;
+        r_minus_1[0] -= 1;
+        assert_eq!(r_minus_1, (-Scalar::one()).to_bytes());
+        assert_eq!(32, S);
+    }
+
+    #[test]
+    fn root_of_unity_order() {
+        assert_eq!(Scalar::one(), root_of_unity(0));
+        assert_eq!(-Scalar::one(), root_of_unity(1));
+        for log_n in 1..=S {
+            let root = root_of_unity(log_n);
+            // `root^(2^(log_n - 1))` is `-1`, so the order is exactly `2^log_n`.
+            let mut power = root;
+            for _ in 1..log_n {
+                power = power.square();
+            }
+            assert_eq!(-Scalar::one(), power);
+            assert_eq!(Scalar::one(), power.square());
+        }
+        assert_eq!(root_of_unity(5), root_of_unity(6).square());
+    }
+
+    #[test]
+    #[should_panic]
+    fn root_of_unity_too_large() {
+        root_of_unity(S + 1);
+    }
+}
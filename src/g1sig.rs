@@ -0,0 +1,227 @@
+//! The "min-sig" BLS configuration: signatures in G1 and public keys in G2.
+//!
+//! The rest of the crate uses the "min-pk" layout, with 48 byte public keys in G1 and 96 byte
+//! signatures in G2. Here the roles are swapped: signatures are 48 bytes and public keys 96, which
+//! suits protocols that send or store many more signatures than keys. The secret keys and key sets
+//! are the same in both layouts; only the signing and verification functions differ. Artifacts of
+//! one layout are distinct types and never verify under the other.
+
+use crate::util::{interpolate_at_zero, into_scalar_plus_1};
+use crate::IntoScalar;
+use anyhow::{anyhow, bail, Result};
+use bls12_381::{pairing, G1Affine, G1Projective, G2Affine, G2Projective};
+use group::{Curve, Group};
+use std::convert::TryInto;
+
+pub use crate::util::hash_g1;
+
+/// The size of a compressed `SignatureG1`.
+pub const SIG_SIZE: usize = 48;
+
+/// The size of a compressed `PublicKeyG2`.
+pub const PK_SIZE: usize = 96;
+
+/// A signature in G1.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SignatureG1(pub G1Projective);
+
+impl SignatureG1 {
+    /// Returns the compressed encoding of the signature.
+    pub fn to_bytes(&self) -> [u8; SIG_SIZE] {
+        self.0.to_affine().to_compressed()
+    }
+
+    /// Decodes a signature from its compressed encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes: &[u8; SIG_SIZE] = match bytes.try_into() {
+            Ok(bytes) => bytes,
+            Err(_) => bail!(
+                "Invalid G1 signature length {}, expected {}",
+                bytes.len(),
+                SIG_SIZE
+            ),
+        };
+        let affine: G1Affine = Option::from(G1Affine::from_compressed(bytes))
+            .ok_or_else(|| anyhow!("Invalid G1 signature encoding"))?;
+        Ok(SignatureG1(affine.into()))
+    }
+}
+
+/// A signature share in G1.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SignatureShareG1(pub SignatureG1);
+
+/// A public key in G2.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PublicKeyG2(pub G2Projective);
+
+impl PublicKeyG2 {
+    /// Returns `true` if `sig` is this key's signature of `msg`.
+    pub fn verify<M: AsRef<[u8]>>(&self, sig: &SignatureG1, msg: M) -> bool {
+        let gt1 = pairing(&G1Affine::from(sig.0), &G2Affine::generator());
+        let gt2 = pairing(&G1Affine::from(hash_g1(msg)), &G2Affine::from(self.0));
+        gt1 == gt2
+    }
+
+    /// Returns the compressed encoding of the public key.
+    pub fn to_bytes(&self) -> [u8; PK_SIZE] {
+        self.0.to_affine().to_compressed()
+    }
+
+    /// Decodes a public key from its compressed encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes: &[u8; PK_SIZE] = match bytes.try_into() {
+            Ok(bytes) => bytes,
+            Err(_) => bail!(
+                "Invalid G2 public key length {}, expected {}",
+                bytes.len(),
+                PK_SIZE
+            ),
+        };
+        let affine: G2Affine = Option::from(G2Affine::from_compressed(bytes))
+            .ok_or_else(|| anyhow!("Invalid G2 public key encoding"))?;
+        Ok(PublicKeyG2(affine.into()))
+    }
+}
+
+/// A public key share in G2.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PublicKeyShareG2(pub PublicKeyG2);
+
+impl PublicKeyShareG2 {
+    /// Returns `true` if `sig` is this share's signature share of `msg`.
+    pub fn verify<M: AsRef<[u8]>>(&self, sig: &SignatureShareG1, msg: M) -> bool {
+        self.0.verify(&sig.0, msg)
+    }
+}
+
+/// A public key and an associated set of public key shares, in G2.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKeySetG2 {
+    /// The commitments to the coefficients of a polynomial whose value at `0` is the "master key",
+    /// and value at `i + 1` is key share number `i`.
+    pub commit: Vec<G2Projective>,
+}
+
+impl PublicKeySetG2 {
+    /// Returns the threshold `t`: any set of `t + 1` signature shares can be combined into a full
+    /// signature. This is `0` if the commitment is empty.
+    pub fn threshold(&self) -> usize {
+        self.commit.len().saturating_sub(1)
+    }
+
+    /// Returns the public key, or `None` if the commitment is empty.
+    pub fn public_key(&self) -> Option<PublicKeyG2> {
+        self.commit.first().map(|c| PublicKeyG2(*c))
+    }
+
+    /// Returns the `i`-th public key share.
+    pub fn public_key_share<T: IntoScalar>(&self, i: T) -> PublicKeyShareG2 {
+        let x = into_scalar_plus_1(i);
+        let mut result = match self.commit.last() {
+            Some(c) => *c,
+            None => G2Projective::identity(),
+        };
+        for c in self.commit.iter().rev().skip(1) {
+            result *= x;
+            result += c;
+        }
+        PublicKeyShareG2(PublicKeyG2(result))
+    }
+
+    /// Combines `threshold + 1` signature shares into a signature by the master key.
+    pub fn combine_signatures<'a, T, I>(&self, shares: I) -> Result<SignatureG1>
+    where
+        I: IntoIterator<Item = (T, &'a SignatureShareG1)>,
+        T: IntoScalar,
+    {
+        let samples = shares.into_iter().map(|(i, share)| (i, &(share.0).0));
+        Ok(SignatureG1(interpolate_at_zero(self.threshold(), samples)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SecretKey, SecretKeySet};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn sign_verify() {
+        let sk = SecretKey::random();
+        let pk = sk.public_key_g2();
+        let msg = b"Rip and tear, until it's done";
+        let sig = sk.sign_g1(msg);
+        assert!(pk.verify(&sig, msg));
+        assert!(!pk.verify(&sig, b"Other msg"));
+        assert!(!SecretKey::random().public_key_g2().verify(&sig, msg));
+
+        assert_eq!(SIG_SIZE, sig.to_bytes().len());
+        assert_eq!(sig, SignatureG1::from_bytes(&sig.to_bytes()).unwrap());
+        assert_eq!(pk, PublicKeyG2::from_bytes(&pk.to_bytes()).unwrap());
+    }
+
+    #[test]
+    fn threshold_sign() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys_g2();
+        assert_eq!(2, pk_set.threshold());
+        assert_eq!(sk_set.public_keys_g2(), pk_set);
+
+        let msg = b"Rip and tear, until it's done";
+        let shares: BTreeMap<_, _> = (0..5)
+            .map(|i| (i, sk_set.secret_key_share(i).sign_g1(msg)))
+            .collect();
+        for (i, share) in &shares {
+            let pk_share = sk_set.secret_key_share(*i).public_key_share_g2();
+            assert_eq!(pk_set.public_key_share(*i), pk_share);
+            assert!(pk_share.verify(share, msg));
+        }
+
+        let sig = pk_set
+            .combine_signatures(shares.iter().skip(2))
+            .expect("failed to combine");
+        assert!(pk_set.public_key().unwrap().verify(&sig, msg));
+        assert!(pk_set.combine_signatures(shares.iter().take(2)).is_err());
+
+        // The same key set still works in the min-pk layout.
+        let pk_shares: BTreeMap<_, _> = (0..3)
+            .map(|i| (i, sk_set.secret_key_share(i).sign(msg)))
+            .collect();
+        let sig_g2 = sk_set.public_keys().combine_signatures(&pk_shares).unwrap();
+        assert!(sk_set
+            .public_keys()
+            .public_key()
+            .unwrap()
+            .verify(&sig_g2, msg));
+    }
+
+    #[test]
+    fn empty_key_set() {
+        let pk_set = PublicKeySetG2 { commit: vec![] };
+        assert_eq!(0, pk_set.threshold());
+        assert_eq!(None, pk_set.public_key());
+    }
+
+    #[test]
+    fn layouts_reject_each_other() {
+        let sk = SecretKey::random();
+        let msg = b"Rip and tear, until it's done";
+        let sig_g1 = sk.sign_g1(msg);
+        let sig_g2 = sk.sign(msg);
+        let pk_g1 = sk.public_key();
+        let pk_g2 = sk.public_key_g2();
+
+        // Encodings of one layout don't decode in the other.
+        let sig_g2_bytes = bincode::serialize(&sig_g2).unwrap();
+        assert!(SignatureG1::from_bytes(&sig_g2_bytes).is_err());
+        assert!(SignatureG1::from_bytes(&sig_g2.0.to_affine().to_compressed()).is_err());
+        assert!(PublicKeyG2::from_bytes(&pk_g1.0.to_affine().to_compressed()).is_err());
+        assert!(PublicKeyG2::from_bytes(&sig_g1.to_bytes()).is_err());
+
+        // A min-pk public key has the size of a min-sig signature, but doesn't verify as one.
+        let forged = SignatureG1::from_bytes(&pk_g1.0.to_affine().to_compressed()).unwrap();
+        assert!(!pk_g2.verify(&forged, msg));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::PublicKeyShare;
+use anyhow::{bail, Result};
+
+/// A public key share together with its index in the public key set.
+///
+/// In distributed key generation, each node's public key share is the sum of the shares with the
+/// same index in every dealer's public key set. Keeping the index with the share lets `combine`
+/// catch shares of different nodes being summed by mistake.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IndexedPublicKeyShare {
+    pub index: usize,
+    pub share: PublicKeyShare,
+}
+
+impl IndexedPublicKeyShare {
+    pub fn new(index: usize, share: PublicKeyShare) -> Self {
+        IndexedPublicKeyShare { index, share }
+    }
+
+    /// Returns the sum of the two shares, or an error if their indices differ.
+    pub fn combine(&self, other: &IndexedPublicKeyShare) -> Result<IndexedPublicKeyShare> {
+        if self.index != other.index {
+            bail!(
+                "Cannot combine public key shares with indices {} and {}",
+                self.index,
+                other.index
+            )
+        }
+        Ok(IndexedPublicKeyShare::new(
+            self.index,
+            self.share.combine(&other.share),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretKeySet;
+
+    #[test]
+    fn combine_across_dealers() {
+        let mut rng = rand::thread_rng();
+        let dealers: Vec<_> = (0..3).map(|_| SecretKeySet::random(2, &mut rng)).collect();
+        let index = 3;
+
+        let mut shares = dealers
+            .iter()
+            .map(|d| IndexedPublicKeyShare::new(index, d.public_keys().public_key_share(index)));
+        let first = shares.next().unwrap();
+        let sum = shares.try_fold(first, |acc, s| acc.combine(&s)).unwrap();
+        assert_eq!(index, sum.index);
+
+        // The summed share belongs to the sum of the dealers' secret key shares.
+        let mut sk_share = dealers[0].secret_key_share(index);
+        for d in &dealers[1..] {
+            sk_share += d.secret_key_share(index);
+        }
+        assert_eq!(sk_share.public_key_share(), sum.share);
+
+        let pk_set = dealers[1..]
+            .iter()
+            .fold(dealers[0].public_keys(), |acc, d| {
+                acc.combine(d.public_keys())
+            });
+        assert_eq!(pk_set.public_key_share(index), sum.share);
+
+        let other = IndexedPublicKeyShare::new(index + 1, pk_set.public_key_share(index + 1));
+        assert!(sum.combine(&other).is_err());
+    }
+}
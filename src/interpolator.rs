@@ -0,0 +1,201 @@
+use crate::util::into_scalar_plus_1;
+use crate::IntoScalar;
+use anyhow::{bail, Result};
+use bls12_381::{G1Projective, G2Projective, Scalar};
+use ff::Field;
+use group::Group;
+
+/// Interpolates values at `0` from shares with a fixed set of indices.
+///
+/// The Lagrange weights only depend on the indices, so they are computed once in `new` and reused
+/// by every call. This is useful when the same signers produce several signatures or decryption
+/// shares in a session. As everywhere else, share `i` is the value at `i + 1`.
+///
+/// This is also what `PublicKeySet` uses to combine signature and decryption shares, so the
+/// results are the same as those of the one-shot functions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Interpolator<T = usize> {
+    indices: Vec<T>,
+    weights: Vec<Scalar>,
+}
+
+impl<T: IntoScalar> Interpolator<T> {
+    /// Precomputes the weights for the given share indices. Fails if `indices` is empty or
+    /// contains duplicates.
+    pub fn new(indices: &[T]) -> Result<Self> {
+        if indices.is_empty() {
+            bail!("Cannot interpolate without shares")
+        }
+        let xs: Vec<Scalar> = indices.iter().map(|i| into_scalar_plus_1(*i)).collect();
+        Ok(Interpolator {
+            indices: indices.to_vec(),
+            weights: lagrange_at_zero(&xs)?,
+        })
+    }
+
+    /// Returns the share indices, in the order the values are expected.
+    pub fn indices(&self) -> &[T] {
+        &self.indices
+    }
+
+    /// Returns the Lagrange weights at `0`, one for each index.
+    pub fn weights(&self) -> &[Scalar] {
+        &self.weights
+    }
+
+    /// Returns the value at `0` of the polynomial through the given field elements, e.g. secret
+    /// key shares. `values[k]` is the share with index `indices()[k]`.
+    pub fn interpolate_scalars(&self, values: &[Scalar]) -> Result<Scalar> {
+        self.check_len(values.len())?;
+        Ok(self
+            .weights
+            .iter()
+            .zip(values)
+            .fold(Scalar::zero(), |acc, (w, v)| acc + w * v))
+    }
+
+    /// Returns the value at `0` of the polynomial through the given points in G1, e.g. decryption
+    /// shares. `values[k]` is the share with index `indices()[k]`.
+    pub fn interpolate_g1(&self, values: &[G1Projective]) -> Result<G1Projective> {
+        self.interpolate_group(values)
+    }
+
+    /// Returns the value at `0` of the polynomial through the given points in G2, e.g. signature
+    /// shares. `values[k]` is the share with index `indices()[k]`.
+    pub fn interpolate_g2(&self, values: &[G2Projective]) -> Result<G2Projective> {
+        self.interpolate_group(values)
+    }
+
+    /// Returns the value at `0` of the polynomial through the given elements of any group.
+    pub(crate) fn interpolate_group<G: Group<Scalar = Scalar>>(&self, values: &[G]) -> Result<G> {
+        self.check_len(values.len())?;
+        Ok(self
+            .weights
+            .iter()
+            .zip(values)
+            .fold(G::identity(), |acc, (w, v)| acc + *v * w))
+    }
+
+    fn check_len(&self, len: usize) -> Result<()> {
+        if len != self.indices.len() {
+            bail!(
+                "Expected {} values to interpolate, got {}",
+                self.indices.len(),
+                len
+            )
+        }
+        Ok(())
+    }
+}
+
+/// Returns the value at `0` of each Lagrange polynomial that is `1` at one of the `xs` and `0` at
+/// the others.
+///
+/// Fails if the `xs` aren't distinct: there is no such polynomial then, and combining shares with
+/// a repeated index would silently give a wrong result.
+fn lagrange_at_zero(xs: &[Scalar]) -> Result<Vec<Scalar>> {
+    if let Some(pos) = (1..xs.len()).find(|pos| xs[..*pos].contains(&xs[*pos])) {
+        bail!("Duplicate share index at position {}", pos)
+    }
+    if xs.len() <= 1 {
+        return Ok(vec![Scalar::one(); xs.len()]);
+    }
+
+    // Compute the products `x_prod[i]` of all but the `i`-th entry.
+    let mut x_prod: Vec<Scalar> = Vec::with_capacity(xs.len());
+    let mut tmp = Scalar::one();
+    x_prod.push(tmp);
+    for x in xs.iter().take(xs.len() - 1) {
+        tmp *= x;
+        x_prod.push(tmp);
+    }
+    tmp = Scalar::one();
+    for (i, x) in xs[1..].iter().enumerate().rev() {
+        tmp *= x;
+        x_prod[i] *= &tmp;
+    }
+
+    let mut coeffs = Vec::with_capacity(xs.len());
+    for (mut l0, x) in x_prod.into_iter().zip(xs) {
+        let mut denom = Scalar::one();
+        for x0 in xs.iter().filter(|x0| *x0 != x) {
+            let mut diff = *x0;
+            diff -= x;
+            denom *= &diff;
+        }
+        // The `xs` are distinct, so the denominator is nonzero.
+        l0 *= &denom.invert().unwrap();
+        coeffs.push(l0);
+    }
+    Ok(coeffs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Poly, SecretKeySet};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn matches_one_shot_functions() {
+        let mut rng = rand::thread_rng();
+        let poly = Poly::random(2, &mut rng);
+        let sk_set = SecretKeySet::from(poly.clone());
+        let pk_set = sk_set.public_keys();
+        let indices = [4, 0, 7];
+        let interpolator = Interpolator::new(&indices).unwrap();
+        assert_eq!(indices, interpolator.indices());
+
+        // Secret key shares interpolate to the master key.
+        let values: Vec<_> = indices.iter().map(|i| poly.evaluate(i + 1)).collect();
+        let samples = indices
+            .iter()
+            .map(|i| (into_scalar_plus_1(*i), poly.evaluate(i + 1)));
+        assert_eq!(
+            Poly::interpolate(samples).evaluate(0),
+            interpolator.interpolate_scalars(&values).unwrap()
+        );
+        assert_eq!(
+            poly.evaluate(0),
+            interpolator.interpolate_scalars(&values).unwrap()
+        );
+
+        // Signature shares combine to the master signature.
+        let msg = b"Rip and tear, until it's done";
+        let sig_shares: BTreeMap<_, _> = indices
+            .iter()
+            .map(|i| (*i, sk_set.secret_key_share(*i).sign(msg)))
+            .collect();
+        let values: Vec<_> = indices.iter().map(|i| (sig_shares[i].0).0).collect();
+        let sig = pk_set.combine_signatures(&sig_shares).unwrap();
+        assert_eq!(sig.0, interpolator.interpolate_g2(&values).unwrap());
+
+        // Decryption shares combine to the same point as in `PublicKeySet::decrypt`.
+        let ct = pk_set.public_key().unwrap().encrypt(msg);
+        let dec_shares: Vec<_> = indices
+            .iter()
+            .map(|i| {
+                sk_set
+                    .secret_key_share(*i)
+                    .try_decrypt_share(&ct)
+                    .unwrap()
+                    .0
+            })
+            .collect();
+        let samples = indices.iter().zip(&dec_shares);
+        let expected: G1Projective = pk_set.interpolate_shares(samples).unwrap();
+        assert_eq!(expected, interpolator.interpolate_g1(&dec_shares).unwrap());
+
+        assert!(interpolator.interpolate_g1(&dec_shares[1..]).is_err());
+        assert!(interpolator.interpolate_scalars(&[]).is_err());
+    }
+
+    #[test]
+    fn invalid_indices() {
+        assert!(Interpolator::<usize>::new(&[]).is_err());
+        assert!(Interpolator::new(&[1, 2, 1]).is_err());
+        let single = Interpolator::new(&[5]).unwrap();
+        let g = G2Projective::generator();
+        assert_eq!(g, single.interpolate_g2(&[g]).unwrap());
+    }
+}
@@ -1,15 +1,35 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+#[cfg(feature = "hash-to-curve")]
+pub mod ciphersuite;
 mod ciphertext;
+mod coin;
+mod error;
+pub mod field;
+pub mod g1sig;
+mod interpolator;
 mod into_scalar;
+mod multi_ciphertext;
+pub mod parse;
 mod pk;
+mod reenc_ciphertext;
+mod sealed_ciphertext;
+mod set_ciphertext;
 mod sig;
+mod sig_aggregator;
 mod sk;
 mod util;
+mod verified_ciphertext;
 
+mod dec_session;
 mod dec_share;
+mod indexed_pk_share;
 mod pk_share;
+mod proven_dec_share;
+mod rekey_share;
+mod share_collection;
+mod share_envelope;
 mod sig_share;
 mod sk_share;
 
@@ -19,19 +39,46 @@ mod commitment;
 mod pk_set;
 mod poly;
 mod sk_set;
+mod weighted;
 
 pub use bicommitment::BivarCommitment;
 pub use bipoly::BivarPoly;
 pub use ciphertext::Ciphertext;
+pub use coin::CoinShare;
 pub use commitment::Commitment;
+pub use dec_session::{DecryptionSession, SessionProgress};
 pub use dec_share::DecryptionShare;
+pub use error::Error;
+pub use indexed_pk_share::IndexedPublicKeyShare;
+pub use interpolator::Interpolator;
 pub use into_scalar::IntoScalar;
-pub use pk::PublicKey;
+pub use multi_ciphertext::{encrypt_multi, MultiCiphertext};
+pub use parse::{parse_any, ParsedType};
+pub use pk::{aggregate_public_keys, PublicKey};
 pub use pk_set::PublicKeySet;
 pub use pk_share::PublicKeyShare;
 pub use poly::Poly;
-pub use sig::Signature;
+pub use proven_dec_share::ProvenDecryptionShare;
+pub use reenc_ciphertext::ReEncryptedCiphertext;
+pub use rekey_share::ReKeyShare;
+pub use sealed_ciphertext::SealedCiphertext;
+pub use set_ciphertext::SetCiphertext;
+pub use share_collection::ShareCollection;
+pub use share_envelope::ShareEnvelope;
+#[allow(deprecated)]
+pub use sig::verify_aggregate_same_message;
+pub use sig::{
+    aggregate, aggregate_in_place, core_aggregate_verify_dedup, deaggregate, fast_aggregate_verify,
+    fast_aggregate_verify_with_key, verify_messages, verify_messages_with_dst, PreparedHashes,
+    Signature, ETH_SIG_SIZE,
+};
+pub use sig_aggregator::SignatureAggregator;
 pub use sig_share::SignatureShare;
 pub use sk::SecretKey;
 pub use sk_set::SecretKeySet;
 pub use sk_share::SecretKeyShare;
+#[allow(deprecated)]
+pub use util::lagrange_coefficients_at_zero;
+pub use util::{hash_g2, hash_g2_with_dst, hash_to_scalar};
+pub use verified_ciphertext::VerifiedCiphertext;
+pub use weighted::{WeightedKeySet, WeightedKeyShare, WeightedSignatureShare};
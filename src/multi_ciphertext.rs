@@ -0,0 +1,132 @@
+use crate::{util, PublicKey};
+use bls12_381::{multi_miller_loop, G1Affine, G1Projective, G2Prepared, G2Projective, Scalar};
+use ff::Field;
+use group::{Curve, Group};
+use rand::RngCore;
+
+/// The associated data of a multi-recipient ciphertext's hash, so that `w` can't be taken for that
+/// of a single-recipient `Ciphertext`.
+const MULTI_TAG: &[u8] = b"rust-tc-multi-v1";
+
+/// A message encrypted to several public keys with a single ephemeral key, created by
+/// `encrypt_multi`.
+///
+/// There is one `u` and one `w` for all recipients, and one payload per recipient, in the order
+/// of the recipients. `w` is bound to every payload, so none of them can be changed without
+/// invalidating the whole ciphertext.
+///
+/// The shared `u` is sent to every recipient, so anyone who sees the ciphertext, including the
+/// recipients themselves, learns that all payloads were encrypted together: use one `Ciphertext`
+/// per recipient if the recipients must not be linkable.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MultiCiphertext {
+    u: G1Projective,
+    payloads: Vec<Vec<u8>>,
+    w: G2Projective,
+}
+
+/// Encrypts the same message to each of the `recipients`, sharing one ephemeral key.
+///
+/// Encrypting to `n` keys separately takes `n` multiplications of the generator and `n` hashes to
+/// G2; here it takes one of each, plus one multiplication of each recipient's key. Recipient `i`
+/// decrypts with `SecretKey::decrypt_multi(ct, i)`.
+pub fn encrypt_multi<R: RngCore, M: AsRef<[u8]>>(
+    recipients: &[PublicKey],
+    msg: M,
+    rng: &mut R,
+) -> MultiCiphertext {
+    let mut r = Scalar::random(rng);
+    let u = G1Affine::generator() * r;
+    let payloads: Vec<Vec<u8>> = recipients
+        .iter()
+        .map(|pk| util::xor_with_hash(pk.0 * r, msg.as_ref()))
+        .collect();
+    let w = hash_payloads(&u.to_affine(), &payloads) * r;
+    util::clear_scalar(&mut r);
+    MultiCiphertext { u, payloads, w }
+}
+
+impl MultiCiphertext {
+    /// Returns `true` if the ciphertext is valid, i.e. `w` matches `u` and all payloads.
+    pub fn verify(&self) -> bool {
+        let u = self.u.to_affine();
+        let hash = G2Prepared::from(hash_payloads(&u, &self.payloads).to_affine());
+        let w = G2Prepared::from(self.w.to_affine());
+        multi_miller_loop(&[(&-G1Affine::generator(), &w), (&u, &hash)])
+            .final_exponentiation()
+            .is_identity()
+            .into()
+    }
+
+    /// Returns the number of recipients.
+    pub fn len(&self) -> usize {
+        self.payloads.len()
+    }
+
+    /// Returns `true` if there are no recipients.
+    pub fn is_empty(&self) -> bool {
+        self.payloads.is_empty()
+    }
+
+    /// Returns the shared ephemeral point `u`.
+    pub fn u(&self) -> &G1Projective {
+        &self.u
+    }
+
+    /// Returns the payload of recipient `index`, if there is one.
+    pub fn payload(&self, index: usize) -> Option<&[u8]> {
+        self.payloads.get(index).map(Vec::as_slice)
+    }
+
+    /// Returns the point `w` that binds `u` to the payloads.
+    pub fn w(&self) -> &G2Projective {
+        &self.w
+    }
+}
+
+/// Returns the hash of `u` and all payloads, each with a length prefix.
+fn hash_payloads(u: &G1Affine, payloads: &[Vec<u8>]) -> G2Projective {
+    let mut msg = Vec::with_capacity(8 + payloads.iter().map(|p| 8 + p.len()).sum::<usize>());
+    msg.extend(&(payloads.len() as u64).to_be_bytes());
+    for payload in payloads {
+        msg.extend(&(payload.len() as u64).to_be_bytes());
+        msg.extend(payload);
+    }
+    util::hash_g1_affine_g2(u, &msg, MULTI_TAG)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, SecretKey};
+
+    #[test]
+    fn encrypt_multi_round_trip() {
+        let mut rng = rand::thread_rng();
+        let sks: Vec<SecretKey> = (0..5).map(|_| SecretKey::random()).collect();
+        let pks: Vec<PublicKey> = sks.iter().map(SecretKey::public_key).collect();
+        let msg = b"Rip and tear, until it's done";
+        let ct = encrypt_multi(&pks, msg, &mut rng);
+        assert_eq!(5, ct.len());
+        assert!(ct.verify());
+        for (i, sk) in sks.iter().enumerate() {
+            assert_eq!(Ok(msg.to_vec()), sk.decrypt_multi(&ct, i));
+            // Each payload only decrypts with its own recipient's key.
+            assert_ne!(ct.payload(i), ct.payload((i + 1) % 5));
+            assert_ne!(Ok(msg.to_vec()), sk.decrypt_multi(&ct, (i + 1) % 5));
+        }
+        assert_eq!(Err(Error::InvalidCiphertext), sks[0].decrypt_multi(&ct, 5));
+
+        // Changing any payload invalidates the ciphertext for everyone.
+        let mut tampered = ct.clone();
+        tampered.payloads[3][0] ^= 1;
+        assert!(!tampered.verify());
+        assert_eq!(
+            Err(Error::InvalidCiphertext),
+            sks[0].decrypt_multi(&tampered, 0)
+        );
+        let mut tampered = ct;
+        tampered.payloads.pop();
+        assert!(!tampered.verify());
+    }
+}
@@ -1,27 +1,246 @@
-use crate::{ciphertext::Ciphertext, sig::Signature, util, util::hash_g2};
-use bls12_381::{pairing, G1Affine, G1Projective, G2Affine, Scalar};
+use crate::sealed_ciphertext::seal_payload;
+use crate::{ciphertext::Ciphertext, sig::Signature, util, util::hash_g2, SealedCiphertext};
+use anyhow::{bail, Result};
+use bls12_381::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Scalar,
+};
 use ff::Field;
-use group::Curve;
+use group::{Curve, Group};
 use rand::rngs::OsRng;
 use rand::RngCore;
+use std::borrow::Borrow;
 use std::cmp::PartialEq;
+use std::convert::TryInto;
+use std::ops::{Add, AddAssign};
 use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
 
-const PKSIZE: usize = 48;
+/// The size of a compressed public key.
+const PK_SIZE: usize = 48;
+
+/// The size of an uncompressed public key.
+const PK_UNCOMPRESSED_SIZE: usize = 96;
+
+/// The domain separation tag of proofs of possession, so that they can't be mistaken for
+/// signatures made with `SecretKey::sign`, in either hashing mode (see `hash_g2_with_dst`). It is
+/// the proof of possession tag of the IETF BLS signature draft, so with the `hash-to-curve`
+/// feature the proofs are compatible with it.
+pub(crate) const POP_DST: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// The domain separation tag of the factors in `derive_child`.
+const DERIVE_DST: &[u8] = b"RUST_TC_DERIVE_V1_";
 
 /// A public key.
+///
+/// Points from untrusted sources should be checked with `from_affine_checked` rather than wrapped
+/// directly: `verify` assumes the key is in the prime-order subgroup.
 #[derive(Copy, Clone, Debug, Eq)]
 pub struct PublicKey(pub G1Projective);
 
 impl PublicKey {
+    /// Returns the public key with the given point, or an error if the point isn't on the curve or
+    /// isn't in the prime-order subgroup.
+    ///
+    /// The identity passes this check; use `is_valid` to reject it as well.
+    pub fn from_affine_checked(p: G1Affine) -> Result<PublicKey> {
+        if !bool::from(p.is_on_curve()) {
+            bail!("Public key is not on the curve")
+        }
+        if !bool::from(p.is_torsion_free()) {
+            bail!("Public key is not in the prime-order subgroup")
+        }
+        Ok(PublicKey(p.into()))
+    }
+
+    /// Returns an identifier of the key: the SHA3-256 hash of its compressed encoding.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        util::sha3_256(&self.to_bytes())
+    }
+
+    /// Returns the compressed encoding of the public key.
+    pub fn to_bytes(&self) -> [u8; PK_SIZE] {
+        self.0.to_affine().to_compressed()
+    }
+
+    /// Decodes a public key from its compressed encoding.
+    ///
+    /// Fails if the length is wrong or the bytes aren't a point in the G1 subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes: &[u8; PK_SIZE] = match bytes.try_into() {
+            Ok(bytes) => bytes,
+            Err(_) => bail!(
+                "Invalid public key length {}, expected {}",
+                bytes.len(),
+                PK_SIZE
+            ),
+        };
+        match Option::<G1Affine>::from(G1Affine::from_compressed(bytes)) {
+            Some(affine) => Ok(PublicKey(affine.into())),
+            None => bail!("Invalid public key encoding"),
+        }
+    }
+
+    /// Returns the uncompressed encoding of the public key, with both coordinates.
+    ///
+    /// This is twice the size of `to_bytes`, for systems that store or expect uncompressed points.
+    pub fn to_bytes_uncompressed(&self) -> [u8; PK_UNCOMPRESSED_SIZE] {
+        self.0.to_affine().to_uncompressed()
+    }
+
+    /// Decodes a public key from its uncompressed encoding.
+    ///
+    /// Fails if the length is wrong or the bytes aren't a point in the G1 subgroup.
+    pub fn from_bytes_uncompressed(bytes: &[u8]) -> Result<Self> {
+        let bytes: &[u8; PK_UNCOMPRESSED_SIZE] = match bytes.try_into() {
+            Ok(bytes) => bytes,
+            Err(_) => bail!(
+                "Invalid uncompressed public key length {}, expected {}",
+                bytes.len(),
+                PK_UNCOMPRESSED_SIZE
+            ),
+        };
+        match Option::<G1Affine>::from(G1Affine::from_uncompressed(bytes)) {
+            Some(affine) => Ok(PublicKey(affine.into())),
+            None => bail!("Invalid uncompressed public key encoding"),
+        }
+    }
+
+    /// Returns the compressed encoding as a `0x`-prefixed hex string, the format eth2 tooling
+    /// uses for BLS public keys.
+    pub fn to_eth_hex(&self) -> String {
+        util::to_eth_hex(&self.to_bytes())
+    }
+
+    /// Decodes a public key from a `0x`-prefixed hex string, see `to_eth_hex`.
+    ///
+    /// The same checks as in `from_bytes` apply. Like eth2 deserialization this accepts the
+    /// identity, which `KeyValidate` rejects: use `is_valid` before verifying with the key.
+    pub fn from_eth_hex(hex: &str) -> Result<Self> {
+        PublicKey::from_bytes(&util::from_eth_hex(hex)?)
+    }
+
     pub fn verify<M: AsRef<[u8]>>(&self, sig: &Signature, msg: M) -> bool {
-        let gt1 = pairing(&G1Affine::generator(), &G2Affine::from(sig.0));
-        let gt2 = pairing(&G1Affine::from(self.0), &G2Affine::from(hash_g2(msg)));
-        gt1 == gt2
+        self.verify_hash(sig, &G2Affine::from(hash_g2(msg)))
+    }
+
+    /// Returns `true` if `sig` is this key's signature of the message under the domain separation
+    /// tag `dst`, see `SecretKey::sign_with_dst`.
+    pub fn verify_with_dst<M: AsRef<[u8]>>(&self, sig: &Signature, dst: &[u8], msg: M) -> bool {
+        self.verify_hash(sig, &G2Affine::from(util::hash_g2_with_dst(dst, msg)))
     }
 
+    /// Returns `true` if `sig` is this key's signature of the message at the given consensus
+    /// `epoch` and `view`, see `SecretKey::sign_with_context`.
+    pub fn verify_with_context<M: AsRef<[u8]>>(
+        &self,
+        sig: &Signature,
+        msg: M,
+        epoch: u64,
+        view: u64,
+    ) -> bool {
+        self.verify_with_dst(sig, &util::context_dst(epoch, view), msg)
+    }
+
+    /// Returns `true` if `sig` is this key's signature of the message with the given `hash_g2`.
+    ///
+    /// When many signatures of the same message are checked, this avoids hashing it every time. It
+    /// also lets a verifier check a signature against a signer's earlier commitment to the hash,
+    /// before the message is revealed. The result only means something if `hash` really is
+    /// `hash_g2` of the message: this key's signature of an arbitrary point says nothing about any
+    /// message.
+    pub fn verify_hash(&self, sig: &Signature, hash: &G2Affine) -> bool {
+        // `e(g, sig) == e(pk, hash)`, with a single final exponentiation.
+        let sig = G2Prepared::from(G2Affine::from(sig.0));
+        let hash = G2Prepared::from(*hash);
+        let pk = G1Affine::from(self.0);
+        let gt = multi_miller_loop(&[(&-G1Affine::generator(), &sig), (&pk, &hash)])
+            .final_exponentiation();
+        bool::from(gt.is_identity())
+    }
+
+    /// Returns `true` if every signature in `items` is valid for its message under this key.
+    ///
+    /// Each signature and message hash is multiplied by a random 128-bit scalar from `rng`, and
+    /// the sums are checked with a single pairing equation. This is much faster than verifying
+    /// the signatures one by one, and an invalid signature makes the check fail except with
+    /// negligible probability. It doesn't tell which signature is invalid: use `invalid_in_batch`
+    /// for that. An empty batch is valid.
+    pub fn verify_batch<R: RngCore>(&self, items: &[(&[u8], &Signature)], rng: &mut R) -> bool {
+        let mut sig_sum = G2Projective::identity();
+        let mut hash_sum = G2Projective::identity();
+        for (msg, sig) in items {
+            let r = Scalar::from_raw([rng.next_u64(), rng.next_u64() | 1, 0, 0]);
+            sig_sum += sig.0 * r;
+            hash_sum += hash_g2(msg) * r;
+        }
+        let sig = G2Prepared::from(G2Affine::from(sig_sum));
+        let hash = G2Prepared::from(G2Affine::from(hash_sum));
+        let pk = G1Affine::from(self.0);
+        let gt = multi_miller_loop(&[(&-G1Affine::generator(), &sig), (&pk, &hash)])
+            .final_exponentiation();
+        bool::from(gt.is_identity())
+    }
+
+    /// Returns the positions of the invalid signatures in `items`, verifying each one separately.
+    ///
+    /// This is the slow path after `verify_batch` fails.
+    pub fn invalid_in_batch(&self, items: &[(&[u8], &Signature)]) -> Vec<usize> {
+        items
+            .iter()
+            .enumerate()
+            .filter(|(_, (msg, sig))| !self.verify(sig, msg))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Derives a child public key for the given index.
+    ///
+    /// This matches `SecretKey::derive_child`: the child of the public key is the public key of the
+    /// child secret key.
+    pub fn derive_child(&self, index: &[u8]) -> PublicKey {
+        PublicKey(self.0 * self.child_scalar(index))
+    }
+
+    /// Returns the factor by which a key is multiplied to derive the child at `index`.
+    pub(crate) fn child_scalar(&self, index: &[u8]) -> Scalar {
+        let mut bytes = self.0.to_affine().to_compressed().to_vec();
+        bytes.extend(index);
+        util::hash_to_scalar(&bytes, DERIVE_DST)
+    }
+
+    /// Returns `true` if `pop` is a proof of possession of the secret key, see
+    /// `SecretKey::prove_possession`.
+    pub fn verify_possession(&self, pop: &Signature) -> bool {
+        self.is_valid() && self.verify_with_dst(pop, POP_DST, self.to_bytes())
+    }
+
+    /// Returns the sum of the public keys after checking a proof of possession for each of them.
+    ///
+    /// This is the safe way to aggregate keys for `fast_aggregate_verify`: with the proofs, no
+    /// signer can choose their key as a function of the others'. Fails if `pks` is empty, if the
+    /// numbers of keys and proofs differ, or with the index of the first invalid proof.
+    pub fn aggregate_checked(pks: &[PublicKey], pops: &[Signature]) -> Result<PublicKey> {
+        if pks.len() != pops.len() {
+            bail!(
+                "Got {} public keys but {} proofs of possession",
+                pks.len(),
+                pops.len()
+            )
+        }
+        if let Some(i) = pks
+            .iter()
+            .zip(pops)
+            .position(|(pk, pop)| !pk.verify_possession(pop))
+        {
+            bail!("Invalid proof of possession for public key {}", i)
+        }
+        aggregate_public_keys(pks)
+    }
+
+    /// Returns `false` if this is the identity, i.e. the public key of the zero secret key, which
+    /// would accept the identity as a signature of any message.
     pub fn is_valid(&self) -> bool {
-        self.0.to_affine().to_compressed().len() == PKSIZE
+        !bool::from(self.0.is_identity())
     }
 
     pub fn encrypt<M: AsRef<[u8]>>(&self, msg: M) -> Ciphertext {
@@ -30,14 +249,90 @@ impl PublicKey {
 
     /// Encrypts the message.
     pub fn encrypt_with_rng<R: RngCore, M: AsRef<[u8]>>(&self, rng: &mut R, msg: M) -> Ciphertext {
+        self.encrypt_with_aad_and_rng(rng, msg, [])
+    }
+
+    /// Encrypts the message, binding the ciphertext to the associated data `aad`.
+    ///
+    /// The associated data isn't encrypted or included in the ciphertext, but the ciphertext only
+    /// verifies and decrypts with the same `aad`, so it can't be replayed in another context. An
+    /// empty `aad` gives a ciphertext that the functions without associated data accept.
+    pub fn encrypt_with_aad<M: AsRef<[u8]>, A: AsRef<[u8]>>(&self, msg: M, aad: A) -> Ciphertext {
+        self.encrypt_with_aad_and_rng(&mut OsRng, msg, aad)
+    }
+
+    /// Encrypts the message with a hybrid scheme, suited to large messages.
+    ///
+    /// A random key is encrypted with `encrypt`, and the message with XChaCha20-Poly1305 under
+    /// that key. See `SealedCiphertext`.
+    pub fn encrypt_sealed<M: AsRef<[u8]>>(&self, msg: M) -> SealedCiphertext {
+        self.encrypt_sealed_with_rng(&mut OsRng, msg)
+    }
+
+    /// Encrypts the message with a hybrid scheme, using the given random number generator.
+    pub fn encrypt_sealed_with_rng<R: RngCore, M: AsRef<[u8]>>(
+        &self,
+        rng: &mut R,
+        msg: M,
+    ) -> SealedCiphertext {
+        let mut key = [0u8; 32];
+        rng.fill_bytes(&mut key);
+        let header = self.encrypt_with_rng(rng, &key[..]);
+        let payload = seal_payload(&key, msg.as_ref());
+        key.zeroize();
+        SealedCiphertext { header, payload }
+    }
+
+    /// Encrypts the message with the given random number generator, binding the ciphertext to the
+    /// associated data `aad`.
+    pub fn encrypt_with_aad_and_rng<R: RngCore, M: AsRef<[u8]>, A: AsRef<[u8]>>(
+        &self,
+        rng: &mut R,
+        msg: M,
+        aad: A,
+    ) -> Ciphertext {
         let r: Scalar = Scalar::random(rng);
         let u = G1Affine::generator() * r;
         let v: Vec<u8> = {
             let g = self.0 * r;
             util::xor_with_hash(g, msg.as_ref())
         };
-        let w = util::hash_g1_g2(u, &v) * r;
-        Ciphertext(u, v, w)
+        let w = util::hash_g1_g2_aad(u, &v, aad.as_ref()) * r;
+        Ciphertext::from_parts(u, v, w)
+    }
+}
+
+/// Returns the sum of the public keys, which verifies the sum of their signatures of a common
+/// message.
+///
+/// Fails if `pks` is empty or contains the identity. The keys must come with proofs of possession
+/// of their secret keys: otherwise a signer can choose their key to cancel out the others'.
+pub fn aggregate_public_keys(pks: &[PublicKey]) -> Result<PublicKey> {
+    if pks.is_empty() {
+        bail!("Cannot aggregate an empty list of public keys")
+    }
+    let mut aggregate = PublicKey(G1Projective::identity());
+    for pk in pks {
+        if !pk.is_valid() {
+            bail!("Cannot aggregate the identity public key")
+        }
+        aggregate += pk;
+    }
+    Ok(aggregate)
+}
+
+impl<B: Borrow<PublicKey>> AddAssign<B> for PublicKey {
+    fn add_assign(&mut self, rhs: B) {
+        self.0 += rhs.borrow().0;
+    }
+}
+
+impl<B: Borrow<PublicKey>> Add<B> for PublicKey {
+    type Output = PublicKey;
+
+    fn add(mut self, rhs: B) -> PublicKey {
+        self += rhs;
+        self
     }
 }
 
@@ -55,7 +350,14 @@ impl ConstantTimeEq for PublicKey {
 
 #[cfg(test)]
 mod tests {
+    use super::PublicKey;
+    use crate::sig::Signature;
     use crate::sk::SecretKey;
+    use crate::util::hash_g2;
+    use crate::{Error, SecretKeySet};
+    use bls12_381::{G1Affine, G1Projective};
+    use group::{Curve, Group};
+    use std::collections::BTreeMap;
     // use rand::{thread_rng, Rng};
 
     // TODO: Fix me
@@ -73,11 +375,38 @@ mod tests {
     //     // println!("eq?: {:?}", pk1 == pk2);
     // }
 
+    #[test]
+    fn verify_batch() {
+        let mut rng = rand::thread_rng();
+        let sk = SecretKey::random();
+        let pk = sk.public_key();
+        let msgs: Vec<Vec<u8>> = (0..50u32).map(|i| i.to_be_bytes().to_vec()).collect();
+        let mut sigs: Vec<_> = msgs.iter().map(|msg| sk.sign(msg)).collect();
+
+        let items: Vec<(&[u8], &Signature)> = msgs.iter().map(Vec::as_slice).zip(&sigs).collect();
+        assert!(pk.verify_batch(&items, &mut rng));
+        assert!(pk.invalid_in_batch(&items).is_empty());
+        assert!(pk.verify_batch(&[], &mut rng));
+
+        sigs[17] = sk.sign(b"Rip and tear, until it's done");
+        let items: Vec<(&[u8], &Signature)> = msgs.iter().map(Vec::as_slice).zip(&sigs).collect();
+        assert!(!pk.verify_batch(&items, &mut rng));
+        assert_eq!(vec![17], pk.invalid_in_batch(&items));
+
+        // Valid signatures under another key don't pass either.
+        let other = SecretKey::random();
+        let other_sigs: Vec<_> = msgs.iter().map(|msg| other.sign(msg)).collect();
+        let items: Vec<(&[u8], &Signature)> =
+            msgs.iter().map(Vec::as_slice).zip(&other_sigs).collect();
+        assert!(!pk.verify_batch(&items, &mut rng));
+    }
+
     #[test]
     fn valid() {
         let sk = SecretKey::random();
         let pk = sk.public_key();
-        assert!(pk.is_valid())
+        assert!(pk.is_valid());
+        assert!(!PublicKey(G1Projective::identity()).is_valid());
     }
 
     #[test]
@@ -87,11 +416,8 @@ mod tests {
         let msg = b"Rip and tear, until it's done";
         let encrypted = pk.encrypt(msg);
         assert!(encrypted.verify());
-        if let Some(decrypted) = sk.decrypt(&encrypted) {
-            assert_eq!(decrypted, msg)
-        } else {
-            assert!(false)
-        }
+        let decrypted = sk.try_decrypt(&encrypted).expect("invalid ciphertext");
+        assert_eq!(decrypted, msg)
     }
 
     #[test]
@@ -103,11 +429,10 @@ mod tests {
         let msg = b"Rip and tear, until it's done";
         let encrypted = pk.encrypt(msg);
         assert!(encrypted.verify());
-        if let Some(decrypted) = other_sk.decrypt(&encrypted) {
-            assert_eq!(decrypted, msg)
-        } else {
-            assert!(false)
-        }
+        let decrypted = other_sk
+            .try_decrypt(&encrypted)
+            .expect("invalid ciphertext");
+        assert_eq!(decrypted, msg)
     }
 
     #[test]
@@ -119,10 +444,142 @@ mod tests {
         let other_msg = b"Don't Rip and tear, until it's done";
         let encrypted = pk.encrypt(msg);
         assert!(encrypted.verify());
-        if let Some(decrypted) = sk.decrypt(&encrypted) {
-            assert_eq!(decrypted, other_msg)
-        } else {
-            assert!(false)
+        let decrypted = sk.try_decrypt(&encrypted).expect("invalid ciphertext");
+        assert_eq!(decrypted, other_msg)
+    }
+
+    #[test]
+    fn verify_hash() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+        let hash = hash_g2(msg).to_affine();
+        let other_hash = hash_g2(b"Other msg").to_affine();
+
+        let sk = SecretKey::random();
+        let sig = sk.sign(msg);
+        assert!(sk.public_key().verify(&sig, msg));
+        assert!(sk.public_key().verify_hash(&sig, &hash));
+        assert!(!sk.public_key().verify_hash(&sig, &other_hash));
+
+        for i in 0..4 {
+            let pk_share = pk_set.public_key_share(i);
+            let sig_share = sk_set.secret_key_share(i).sign(msg);
+            assert!(pk_share.verify(&sig_share, msg));
+            assert!(pk_share.verify_hash(&sig_share, &hash));
+            assert!(!pk_share.verify_hash(&sig_share, &other_hash));
+            let other_share = pk_set.public_key_share(i + 1);
+            assert_eq!(
+                other_share.verify(&sig_share, msg),
+                other_share.verify_hash(&sig_share, &hash)
+            );
+        }
+    }
+
+    #[test]
+    fn from_affine_checked() {
+        let pk = SecretKey::random().public_key();
+        let checked = PublicKey::from_affine_checked(pk.0.to_affine()).expect("valid key");
+        assert_eq!(pk, checked);
+
+        // `(4, y)` is on the curve, but has a component outside the prime-order subgroup.
+        let y = "0a989badd40d6212b33cffc3f3763e9bc760f988c9926b26da9dd85e928483446346b8ed00e1de5d5ea93e354abe706c";
+        let mut bytes = [0u8; 96];
+        bytes[47] = 4;
+        for (i, byte) in bytes[48..].iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&y[2 * i..2 * i + 2], 16).unwrap();
+        }
+        let point = G1Affine::from_uncompressed_unchecked(&bytes).unwrap();
+        assert!(bool::from(point.is_on_curve()));
+        assert!(bool::from(G1Affine::from_uncompressed(&bytes).is_none()));
+        assert!(PublicKey::from_affine_checked(point).is_err());
+    }
+
+    #[test]
+    fn enc_dec_with_aad() {
+        let sk = SecretKey::random();
+        let pk = sk.public_key();
+        let msg = b"Rip and tear, until it's done";
+        let ct = pk.encrypt_with_aad(msg, b"epoch=7");
+        assert!(ct.verify_with_aad(b"epoch=7"));
+        assert_eq!(msg.to_vec(), sk.decrypt_with_aad(&ct, b"epoch=7").unwrap());
+
+        // The ciphertext can't be replayed in another context.
+        assert!(!ct.verify_with_aad(b"epoch=8"));
+        assert!(!ct.verify());
+        assert_eq!(
+            Err(Error::InvalidCiphertext),
+            sk.decrypt_with_aad(&ct, b"epoch=8")
+        );
+        assert!(sk.try_decrypt(&ct).is_err());
+
+        // Empty associated data is the same as none.
+        let ct = pk.encrypt_with_aad(msg, b"");
+        assert!(ct.verify());
+        assert_eq!(msg.to_vec(), sk.try_decrypt(&ct).unwrap());
+        let ct = pk.encrypt(msg);
+        assert!(ct.verify_with_aad(b""));
+        assert_eq!(msg.to_vec(), sk.decrypt_with_aad(&ct, b"").unwrap());
+    }
+
+    #[test]
+    fn threshold_dec_with_aad() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(1, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+        let ct = pk_set
+            .public_key()
+            .unwrap()
+            .encrypt_with_aad(msg, b"epoch=7");
+
+        let mut shares = BTreeMap::new();
+        for i in 0..2 {
+            let sk_share = sk_set.secret_key_share(i);
+            let pk_share = pk_set.public_key_share(i);
+            assert!(sk_share.decrypt_share_with_aad(&ct, b"epoch=8").is_err());
+            assert!(sk_share.try_decrypt_share(&ct).is_err());
+            let share = sk_share.decrypt_share_with_aad(&ct, b"epoch=7").unwrap();
+            assert!(pk_share.verify_decryption_share_with_aad(&share, &ct, b"epoch=7"));
+            assert!(!pk_share.verify_decryption_share_with_aad(&share, &ct, b"epoch=8"));
+            shares.insert(i, share);
         }
+        assert_eq!(msg.to_vec(), pk_set.decrypt(&shares, &ct).unwrap());
+    }
+
+    #[test]
+    fn uncompressed_bytes() {
+        let pk = SecretKey::random().public_key();
+        let compressed = pk.to_bytes();
+        let uncompressed = pk.to_bytes_uncompressed();
+        assert_eq!(96, uncompressed.len());
+        assert_eq!(pk, PublicKey::from_bytes(&compressed).unwrap());
+        assert_eq!(
+            pk,
+            PublicKey::from_bytes_uncompressed(&uncompressed).unwrap()
+        );
+        // Both encodings start with `x`, apart from the flag bits.
+        assert_eq!(compressed[0] & 0x1f, uncompressed[0]);
+        assert_eq!(compressed[1..], uncompressed[1..48]);
+
+        let identity = PublicKey(G1Projective::identity());
+        let bytes = identity.to_bytes_uncompressed();
+        assert_eq!(
+            identity,
+            PublicKey::from_bytes_uncompressed(&bytes).unwrap()
+        );
+
+        assert!(PublicKey::from_bytes_uncompressed(&compressed).is_err());
+        assert!(PublicKey::from_bytes(&uncompressed).is_err());
+        let mut off_curve = uncompressed;
+        off_curve[95] ^= 1;
+        assert!(PublicKey::from_bytes_uncompressed(&off_curve).is_err());
+        // A point on the curve with `x = 4`, outside the prime-order subgroup.
+        let mut bytes = [0u8; 48];
+        bytes[0] = 0x80;
+        bytes[47] = 4;
+        let point = G1Affine::from_compressed_unchecked(&bytes).unwrap();
+        assert!(PublicKey::from_bytes_uncompressed(&point.to_uncompressed()).is_err());
     }
 }
@@ -1,14 +1,24 @@
+use crate::coin::coin_message;
+use crate::sealed_ciphertext::open_payload;
 use crate::util::*;
 use crate::{
-    Ciphertext, Commitment, DecryptionShare, IntoScalar, PublicKey, PublicKeyShare, Signature,
-    SignatureShare,
+    Ciphertext, CoinShare, Commitment, DecryptionShare, Error, Interpolator, IntoScalar, PublicKey,
+    PublicKeyShare, ReEncryptedCiphertext, ReKeyShare, SealedCiphertext, SecretKeyShare,
+    SetCiphertext, Signature, SignatureShare, WeightedSignatureShare,
 };
 use anyhow::{anyhow, bail, Result};
 use bls12_381::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
 use ff::Field;
 use group::prime::PrimeCurve;
+use group::{Curve, Group};
+use rand::rngs::OsRng;
+use rand::RngCore;
 use std::borrow::Borrow;
 use std::hash::{Hash, Hasher};
+use zeroize::{Zeroize, Zeroizing};
+
+/// The domain tag of `PublicKeySet::decrypt_to_key`.
+const KEY_TAG: &[u8] = b"rust-tc-kem-v1";
 
 /// A public key and an associated set of public key shares.
 #[derive(Clone, Debug, PartialEq, Eq, Ord, PartialOrd)]
@@ -37,9 +47,53 @@ impl PublicKeySet {
         self.commit.degree()
     }
 
-    /// Returns the public key.
-    pub fn public_key(&self) -> PublicKey {
-        PublicKey(self.commit.coeff[0])
+    /// Returns `true` if `count` shares are enough to combine, i.e. `count > threshold()`.
+    pub fn has_quorum(&self, count: usize) -> bool {
+        count > self.threshold()
+    }
+
+    /// Returns how many more shares are needed to combine, in addition to `count`; `0` if there
+    /// is a quorum.
+    pub fn missing(&self, count: usize) -> usize {
+        (self.threshold() + 1).saturating_sub(count)
+    }
+
+    /// Returns the public key, or `None` if the commitment is empty.
+    pub fn public_key(&self) -> Option<PublicKey> {
+        self.commit.coeff.first().map(|c| PublicKey(*c))
+    }
+
+    /// Returns an identifier of the set: the SHA3-256 hash of the commitment's encoding.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        sha3_256(&self.commit.to_bytes())
+    }
+
+    /// Encrypts the message to the master key, tagged with this set's fingerprint.
+    ///
+    /// Fails if the commitment is empty, so that there is no master key.
+    pub fn encrypt<M: AsRef<[u8]>>(&self, msg: M) -> Result<SetCiphertext> {
+        self.encrypt_with_rng(&mut OsRng, msg)
+    }
+
+    /// Encrypts the message to the master key with the given random number generator, tagged with
+    /// this set's fingerprint.
+    ///
+    /// Fails if the commitment is empty, so that there is no master key.
+    pub fn encrypt_with_rng<R: RngCore, M: AsRef<[u8]>>(
+        &self,
+        rng: &mut R,
+        msg: M,
+    ) -> Result<SetCiphertext> {
+        let pk = match self.public_key() {
+            Some(pk) => pk,
+            None => bail!("The public key set is empty"),
+        };
+        let fingerprint = self.fingerprint();
+        let ciphertext = pk.encrypt_with_aad_and_rng(rng, msg, fingerprint);
+        Ok(SetCiphertext {
+            fingerprint,
+            ciphertext,
+        })
     }
 
     /// Returns the `i`-th public key share.
@@ -48,132 +102,746 @@ impl PublicKeySet {
         PublicKeyShare(PublicKey(value))
     }
 
+    /// Returns the public key shares `0` to `n - 1`.
+    ///
+    /// This is equal to calling `public_key_share` for each index, but much faster for large `n`:
+    /// after the first `t + 1` shares, each one costs `t` group additions.
+    pub fn all_public_key_shares(&self, n: usize) -> Vec<PublicKeyShare> {
+        self.commit
+            .evaluate_range(n)
+            .into_iter()
+            .map(|value| PublicKeyShare(PublicKey(value)))
+            .collect()
+    }
+
+    /// Returns `true` if `share` is the `i`-th secret key share of this set.
+    ///
+    /// The zero share, e.g. a `Default` placeholder, is never valid.
+    pub fn verify_secret_key_share<T: IntoScalar>(&self, i: T, share: &SecretKeyShare) -> bool {
+        !share.is_zero() && share.public_key_share() == self.public_key_share(i)
+    }
+
+    /// Combines the first `t + 1` signature shares into a signature by the master key.
+    ///
+    /// Fails if there are too few shares, or if one of them is not valid, e.g. the identity: the
+    /// error then downcasts to `Error::InvalidSignature` with the share's position.
     pub fn combine_signatures<'a, T, I>(&self, shares: I) -> Result<Signature>
     where
         I: IntoIterator<Item = (T, &'a SignatureShare)>,
         T: IntoScalar,
     {
+        let shares: Vec<_> = shares.into_iter().take(self.threshold() + 1).collect();
+        if let Some(pos) = shares.iter().position(|(_, share)| !share.is_valid()) {
+            return Err(Error::InvalidSignature(pos).into());
+        }
         let samples = shares.into_iter().map(|(i, share)| (i, &(share.0).0));
-        Ok(Signature(combine_signatures_(
+        Ok(Signature(interpolate_at_zero(
             self.commit.degree(),
             samples,
         )?))
     }
 
+    /// Returns `true` if `share` is the `i`-th coin share of the given instance and round.
+    pub fn verify_coin_share<T: IntoScalar>(
+        &self,
+        i: T,
+        share: &CoinShare,
+        instance: &[u8],
+        round: u64,
+    ) -> bool {
+        self.public_key_share(i)
+            .verify(&share.0, coin_message(instance, round))
+    }
+
+    /// Combines the first `t + 1` coin shares of the given instance and round into the coin value:
+    /// 32 bytes derived from the combined signature with `Signature::to_random_bytes`.
+    ///
+    /// Fails if there are too few shares, or if the combined signature doesn't verify, e.g.
+    /// because one of the shares is for another round. Use `verify_coin_share` to find the faulty
+    /// shares.
+    pub fn combine_coin<'a, T, I>(&self, shares: I, instance: &[u8], round: u64) -> Result<[u8; 32]>
+    where
+        I: IntoIterator<Item = (T, &'a CoinShare)>,
+        T: IntoScalar,
+    {
+        let sig = self.combine_signatures(shares.into_iter().map(|(i, share)| (i, &share.0)))?;
+        match self.public_key() {
+            Some(pk) if pk.verify(&sig, coin_message(instance, round)) => Ok(sig.to_random_bytes()),
+            _ => bail!("Combined coin signature is invalid"),
+        }
+    }
+
+    /// Checks each signature share of `msg` against its public key share, and returns the valid
+    /// `(index, share)` pairs and the indices of the invalid ones, both in input order.
+    ///
+    /// The message is hashed only once. The valid shares can be passed to `combine_signatures`,
+    /// and the invalid indices identify the faulty signers.
+    pub fn partition_valid_shares<'a, M, T, I>(
+        &self,
+        msg: M,
+        shares: I,
+    ) -> (Vec<(usize, SignatureShare)>, Vec<usize>)
+    where
+        M: AsRef<[u8]>,
+        I: IntoIterator<Item = (T, &'a SignatureShare)>,
+        T: Borrow<usize>,
+    {
+        let hash = hash_g2(msg).to_affine();
+        let mut valid = Vec::new();
+        let mut invalid = Vec::new();
+        for (i, share) in shares {
+            let i = *i.borrow();
+            if self.public_key_share(i).verify_hash(share, &hash) {
+                valid.push((i, share.clone()));
+            } else {
+                invalid.push(i);
+            }
+        }
+        (valid, invalid)
+    }
+
+    /// Combines `threshold + 1` shares of any group element into the element for the master key.
+    ///
+    /// Share `i` must be the element computed with the `i`-th secret key share, in the same way as
+    /// the result is computed with the master key, e.g. a point multiplied by the key share. This
+    /// is how `combine_signatures` and `decrypt` combine their shares, so protocols can use it for
+    /// their own group elements.
+    pub fn interpolate_shares<G, B, T, I>(&self, shares: I) -> Result<G>
+    where
+        G: Group<Scalar = Scalar>,
+        I: IntoIterator<Item = (T, B)>,
+        T: IntoScalar,
+        B: Borrow<G>,
+    {
+        interpolate_at_zero(self.threshold(), shares)
+    }
+
+    /// Combines the signature shares of participants in a `WeightedKeySet`. Their total weight
+    /// must exceed the threshold.
+    pub fn combine_weighted_signatures<'a, I>(&self, shares: I) -> Result<Signature>
+    where
+        I: IntoIterator<Item = &'a WeightedSignatureShare>,
+    {
+        self.combine_signatures(shares.into_iter().flat_map(WeightedSignatureShare::shares))
+    }
+
+    /// Combines signature shares using the precomputed Lagrange weights of `interpolator`.
+    ///
+    /// `shares` must yield the shares with the interpolator's indices, in the same order. This
+    /// saves recomputing the weights when combining many signatures from the same set of signers.
+    /// Like `combine_signatures`, this fails with `Error::InvalidSignature` and the share's
+    /// position if one of them is not valid.
+    pub fn combine_signatures_with_coeffs<'a, T, I>(
+        &self,
+        interpolator: &Interpolator<T>,
+        shares: I,
+    ) -> Result<Signature>
+    where
+        I: IntoIterator<Item = &'a SignatureShare>,
+        T: IntoScalar,
+    {
+        let count = interpolator.indices().len();
+        if count <= self.threshold() {
+            bail!("need t+1 = {} shares, got {}", self.threshold() + 1, count)
+        }
+        let shares: Vec<&SignatureShare> = shares.into_iter().collect();
+        if let Some(pos) = shares.iter().position(|share| !share.is_valid()) {
+            return Err(Error::InvalidSignature(pos).into());
+        }
+        let values: Vec<G2Projective> = shares.iter().map(|share| (share.0).0).collect();
+        Ok(Signature(interpolator.interpolate_g2(&values)?))
+    }
+
     /// Combine two PublicKeySet into a single one (used from threshold generation)
+    ///
+    /// The result's threshold is the larger of the two thresholds: combining sets of different
+    /// thresholds raises the number of shares needed for the smaller one. Use `try_combine` if the
+    /// thresholds are expected to match.
     pub fn combine(&self, other: PublicKeySet) -> PublicKeySet {
         let mut commit = self.commit.clone();
         commit += &other.commit;
         PublicKeySet::from(commit)
     }
 
+    /// Combines two public key sets like `combine`, but fails if their thresholds differ.
+    pub fn try_combine(&self, other: PublicKeySet) -> Result<PublicKeySet> {
+        if self.threshold() != other.threshold() {
+            bail!(
+                "Cannot combine public key sets with thresholds {} and {}",
+                self.threshold(),
+                other.threshold()
+            )
+        }
+        Ok(self.combine(other))
+    }
+
+    /// Combines the first `t + 1` decryption shares of `ct` into the message.
+    ///
+    /// The shares should be verified first, with `PublicKeyShare::verify_decryption_share`. Fails
+    /// if there are too few shares, or if one of them is not valid, e.g. the identity: the error
+    /// then downcasts to `Error::InvalidDecryptionShare` with the share's position.
     pub fn decrypt<'a, T, I>(&self, shares: I, ct: &Ciphertext) -> Result<Vec<u8>>
     where
         I: IntoIterator<Item = (T, &'a DecryptionShare)>,
         T: IntoScalar,
     {
+        let g = self.combine_decryption_shares(shares)?;
+        Ok(xor_with_hash(g.into(), ct.v()))
+    }
+
+    /// Combines decryption shares into the message like `decrypt`, but returns it in a buffer that
+    /// is overwritten with zeros when dropped.
+    pub fn decrypt_zeroizing<'a, T, I>(
+        &self,
+        shares: I,
+        ct: &Ciphertext,
+    ) -> Result<Zeroizing<Vec<u8>>>
+    where
+        I: IntoIterator<Item = (T, &'a DecryptionShare)>,
+        T: IntoScalar,
+    {
+        Ok(Zeroizing::new(self.decrypt(shares, ct)?))
+    }
+
+    /// Combines decryption shares into the message like `decrypt`, writes it to the start of `out`
+    /// and returns its length.
+    ///
+    /// Fails with `Error::BufferTooSmall` if `out` can't hold the message, leaving it unchanged.
+    pub fn decrypt_into<'a, T, I>(
+        &self,
+        shares: I,
+        ct: &Ciphertext,
+        out: &mut [u8],
+    ) -> Result<usize>
+    where
+        I: IntoIterator<Item = (T, &'a DecryptionShare)>,
+        T: IntoScalar,
+    {
+        if out.len() < ct.v().len() {
+            return Err(Error::BufferTooSmall(ct.v().len()).into());
+        }
+        let g = self.combine_decryption_shares(shares)?;
+        xor_with_hash_into(g.into(), ct.v(), out);
+        Ok(ct.v().len())
+    }
+
+    /// Combines decryption shares of `ct` into a 32 byte symmetric key, for hybrid encryption.
+    ///
+    /// Instead of unmasking the payload, this hashes the combined point, bound to `ct`'s `u`, with
+    /// SHA3-256 under its own domain tag. The key depends only on `ct` and the master secret key,
+    /// so any `t + 1` valid shares give the same one. As with `decrypt`, the shares should be
+    /// verified first.
+    pub fn decrypt_to_key<'a, T, I>(&self, shares: I, ct: &Ciphertext) -> Result<[u8; 32]>
+    where
+        I: IntoIterator<Item = (T, &'a DecryptionShare)>,
+        T: IntoScalar,
+    {
+        let g = self.combine_decryption_shares(shares)?;
+        let mut input = KEY_TAG.to_vec();
+        input.extend_from_slice(&g.to_compressed());
+        input.extend_from_slice(&ct.u().to_affine().to_compressed());
+        let key = sha3_256(&input);
+        input.zeroize();
+        Ok(key)
+    }
+
+    /// Interpolates the first `t + 1` decryption shares of a ciphertext into the point that masks
+    /// its payload, i.e. `u * sk` for the master secret key `sk`.
+    ///
+    /// `decrypt` hashes this point and XORs the hash with `v`; protocols that derive their own key
+    /// from it, or prove the decryption, can use it directly. Fails like `decrypt` if there are
+    /// too few shares or one of them is invalid, and as with `decrypt` the shares should be
+    /// verified first.
+    pub fn combine_decryption_shares<'a, T, I>(&self, shares: I) -> Result<G1Affine>
+    where
+        I: IntoIterator<Item = (T, &'a DecryptionShare)>,
+        T: IntoScalar,
+    {
+        let shares: Vec<_> = shares.into_iter().take(self.threshold() + 1).collect();
+        if let Some(pos) = shares.iter().position(|(_, share)| !share.is_valid()) {
+            return Err(Error::InvalidDecryptionShare(pos).into());
+        }
         let samples = shares.into_iter().map(|(i, share)| (i, &share.0));
-        let g = decrypt_(self.commit.degree(), samples)?;
-        Ok(xor_with_hash(g, &ct.1))
+        let g: G1Projective = interpolate_at_zero(self.commit.degree(), samples)?;
+        Ok(g.to_affine())
+    }
+
+    /// Combines decryption shares of the header of `ct` into its key, and opens the payload.
+    ///
+    /// As with `decrypt`, the shares should be verified first. Fails if there are too few shares,
+    /// or if the payload doesn't open under the combined key, e.g. because it was tampered with.
+    pub fn decrypt_sealed<'a, T, I>(&self, shares: I, ct: &SealedCiphertext) -> Result<Vec<u8>>
+    where
+        I: IntoIterator<Item = (T, &'a DecryptionShare)>,
+        T: IntoScalar,
+    {
+        let key = Zeroizing::new(self.decrypt(shares, &ct.header)?);
+        Ok(open_payload(&key, &ct.payload)?)
     }
-}
 
-// TODO: Figure out how to combine these two functions
-
-fn decrypt_<B, T, I>(t: usize, items: I) -> Result<G1Projective>
-where
-    I: IntoIterator<Item = (T, B)>,
-    T: IntoScalar,
-    B: Borrow<G1Projective>,
-{
-    let samples: Vec<_> = items
-        .into_iter()
-        .take(t + 1)
-        .map(|(i, sample)| (into_scalar_plus_1(i), sample))
-        .collect();
-    if samples.len() <= t {
-        bail!("not enough shares")
-    }
-
-    if t == 0 {
-        return Ok(*samples[0].1.borrow());
-    }
-
-    // Compute the products `x_prod[i]` of all but the `i`-th entry.
-    let mut x_prod: Vec<Scalar> = Vec::with_capacity(t);
-    let mut tmp = Scalar::one();
-    x_prod.push(tmp);
-    for (x, _) in samples.iter().take(t) {
-        tmp *= x;
-        x_prod.push(tmp);
-    }
-    tmp = Scalar::one();
-    for (i, (x, _)) in samples[1..].iter().enumerate().rev() {
-        tmp *= x;
-        x_prod[i] *= &tmp;
-    }
-
-    let mut result = G1Projective::identity();
-    for (mut l0, (x, sample)) in x_prod.into_iter().zip(&samples) {
-        // Compute the value at 0 of the Lagrange polynomial that is `0` at the other data
-        // points but `1` at `x`.
-        let mut denom = Scalar::one();
-        for (x0, _) in samples.iter().filter(|(x0, _)| x0 != x) {
-            let mut diff = *x0;
-            diff -= x;
-            denom *= &diff;
+    /// Combines decryption shares of a ciphertext encrypted with `encrypt` into the message.
+    ///
+    /// Fails if the ciphertext is meant for another set or isn't valid, or if there are too few
+    /// shares. As with `decrypt`, the shares should be verified first.
+    pub fn decrypt_set_ciphertext<'a, T, I>(&self, shares: I, ct: &SetCiphertext) -> Result<Vec<u8>>
+    where
+        I: IntoIterator<Item = (T, &'a DecryptionShare)>,
+        T: IntoScalar,
+    {
+        if ct.fingerprint != self.fingerprint() {
+            bail!("Ciphertext is meant for another public key set")
+        }
+        if !ct.ciphertext.verify_with_aad(ct.fingerprint) {
+            return Err(Error::InvalidCiphertext.into());
         }
-        l0 *= &denom.invert().unwrap();
-        result += sample.borrow() * l0;
+        self.decrypt(shares, &ct.ciphertext)
+    }
+
+    /// Combines re-encryption shares of `ct` into a ciphertext that can be decrypted with the
+    /// target's secret key.
+    pub fn combine_reencryption_shares<'a, T, I>(
+        &self,
+        shares: I,
+        ct: &Ciphertext,
+    ) -> Result<ReEncryptedCiphertext>
+    where
+        I: IntoIterator<Item = (T, &'a ReKeyShare)>,
+        T: IntoScalar,
+    {
+        let shares: Vec<_> = shares.into_iter().collect();
+        let masked = interpolate_at_zero(
+            self.commit.degree(),
+            shares.iter().map(|(i, share)| (*i, &share.0)),
+        )?;
+        let mask = interpolate_at_zero(
+            self.commit.degree(),
+            shares.iter().map(|(i, share)| (*i, &share.1)),
+        )?;
+        Ok(ReEncryptedCiphertext(ct.clone(), masked, mask))
     }
-    Ok(result)
 }
 
-fn combine_signatures_<B, T, I>(t: usize, items: I) -> Result<G2Projective>
-where
-    I: IntoIterator<Item = (T, B)>,
-    T: IntoScalar,
-    B: Borrow<G2Projective>,
-{
-    let samples: Vec<_> = items
-        .into_iter()
-        .take(t + 1)
-        .map(|(i, sample)| (into_scalar_plus_1(i), sample))
-        .collect();
-    if samples.len() <= t {
-        bail!("not enough shares")
-    }
-
-    if t == 0 {
-        return Ok(*samples[0].1.borrow());
-    }
-
-    // Compute the products `x_prod[i]` of all but the `i`-th entry.
-    let mut x_prod: Vec<Scalar> = Vec::with_capacity(t);
-    let mut tmp = Scalar::one();
-    x_prod.push(tmp);
-    for (x, _) in samples.iter().take(t) {
-        tmp *= x;
-        x_prod.push(tmp);
-    }
-    tmp = Scalar::one();
-    for (i, (x, _)) in samples[1..].iter().enumerate().rev() {
-        tmp *= x;
-        x_prod[i] *= &tmp;
-    }
-
-    let mut result = G2Projective::identity();
-    for (mut l0, (x, sample)) in x_prod.into_iter().zip(&samples) {
-        // Compute the value at 0 of the Lagrange polynomial that is `0` at the other data
-        // points but `1` at `x`.
-        let mut denom = Scalar::one();
-        for (x0, _) in samples.iter().filter(|(x0, _)| x0 != x) {
-            let mut diff = *x0;
-            diff -= x;
-            denom *= &diff;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Poly, SecretKeySet};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn partition_valid_shares() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+        let mut shares: BTreeMap<_, _> = (0..7)
+            .map(|i| (i, sk_set.secret_key_share(i).sign(msg)))
+            .collect();
+        // A share of another message, a share under the wrong index, and a random signature.
+        shares.insert(1, sk_set.secret_key_share(1).sign(b"Other msg"));
+        shares.insert(4, sk_set.secret_key_share(5).sign(msg));
+        shares.insert(6, SignatureShare(crate::SecretKey::random().sign(msg)));
+
+        let (valid, invalid) = pk_set.partition_valid_shares(msg, &shares);
+        assert_eq!(vec![1, 4, 6], invalid);
+        let indices: Vec<_> = valid.iter().map(|(i, _)| *i).collect();
+        assert_eq!(vec![0, 2, 3, 5], indices);
+        for (i, share) in &valid {
+            assert_eq!(shares[i], *share);
         }
-        l0 *= &denom.invert().unwrap();
-        result += sample.borrow() * l0;
+
+        let sig = pk_set
+            .combine_signatures(valid.iter().map(|(i, share)| (*i, share)))
+            .expect("failed to combine");
+        assert!(pk_set.public_key().unwrap().verify(&sig, msg));
+
+        let (valid, invalid) = pk_set.partition_valid_shares(msg, &BTreeMap::new());
+        assert!(valid.is_empty() && invalid.is_empty());
+    }
+
+    #[test]
+    fn interpolate_shares() {
+        // The polynomial `5 + X + 3 X²`, whose value at `0` is the master key `5`.
+        let coeff = [5, 1, 3].iter().map(|c| Scalar::from(*c as u64)).collect();
+        let poly = Poly { coeff };
+        let pk_set = SecretKeySet::from(poly.clone()).public_keys();
+
+        // A custom output, e.g. of a VRF: the input point multiplied by the key.
+        let point = hash_g1(b"Rip and tear, until it's done");
+        let shares: BTreeMap<_, _> = (0..5).map(|i| (i, point * poly.evaluate(i + 1))).collect();
+        let expected = point * Scalar::from(5);
+        let combined: G1Projective = pk_set.interpolate_shares(shares.iter().skip(1)).unwrap();
+        assert_eq!(expected, combined);
+        let combined: G1Projective = pk_set.interpolate_shares(shares.iter().rev()).unwrap();
+        assert_eq!(expected, combined);
+        assert!(pk_set
+            .interpolate_shares::<G1Projective, _, _, _>(shares.iter().take(2))
+            .is_err());
+
+        // The same machinery combines public key shares into the public key.
+        let pk_shares: Vec<_> = (0..3)
+            .map(|i| (i, pk_set.public_key_share(i).0 .0))
+            .collect();
+        let pk: G1Projective = pk_set.interpolate_shares(pk_shares).unwrap();
+        assert_eq!(pk_set.public_key().unwrap().0, pk);
+    }
+
+    #[test]
+    fn all_public_key_shares() {
+        let mut rng = rand::thread_rng();
+        let pk_set = SecretKeySet::random(3, &mut rng).public_keys();
+        let shares = pk_set.all_public_key_shares(10);
+        assert_eq!(10, shares.len());
+        for (i, share) in shares.iter().enumerate() {
+            assert_eq!(pk_set.public_key_share(i), *share);
+        }
+        assert!(pk_set.all_public_key_shares(0).is_empty());
+    }
+
+    #[test]
+    fn fingerprint() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        assert_eq!(pk_set.fingerprint(), sk_set.public_keys().fingerprint());
+        let deser: PublicKeySet = PublicKeySet::from(
+            bincode::deserialize::<Commitment>(&bincode::serialize(&pk_set.commit).unwrap())
+                .unwrap(),
+        );
+        assert_eq!(pk_set.fingerprint(), deser.fingerprint());
+
+        let other_set = SecretKeySet::random(2, &mut rng).public_keys();
+        assert_ne!(pk_set.fingerprint(), other_set.fingerprint());
+
+        // A set with the same master key but another threshold is a different set.
+        let mut extended = pk_set.clone();
+        extended.commit.coeff.push(other_set.commit.coeff[1]);
+        assert_eq!(pk_set.public_key(), extended.public_key());
+        assert_ne!(pk_set.fingerprint(), extended.fingerprint());
+
+        let pk = pk_set.public_key().unwrap();
+        assert_eq!(
+            pk.fingerprint(),
+            PublicKey::from_bytes(&pk.to_bytes()).unwrap().fingerprint()
+        );
+        assert_ne!(
+            pk.fingerprint(),
+            other_set.public_key().unwrap().fingerprint()
+        );
+        assert_ne!(pk.fingerprint(), pk_set.fingerprint());
+    }
+
+    #[test]
+    fn set_ciphertext() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(1, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let other_set = SecretKeySet::random(1, &mut rng).public_keys();
+        let msg = b"Rip and tear, until it's done";
+        let ct = pk_set.encrypt(msg).unwrap();
+        assert_eq!(pk_set.fingerprint(), ct.fingerprint);
+        assert!(ct.verify(&pk_set));
+        assert!(!ct.verify(&other_set));
+
+        let shares: BTreeMap<_, _> = (0..2)
+            .map(|i| {
+                let sk_share = sk_set.secret_key_share(i);
+                let share = sk_share.decrypt_share_with_aad(&ct.ciphertext, ct.fingerprint);
+                (i, share.expect("valid ciphertext"))
+            })
+            .collect();
+        assert_eq!(
+            msg.to_vec(),
+            pk_set.decrypt_set_ciphertext(&shares, &ct).unwrap()
+        );
+        assert!(other_set.decrypt_set_ciphertext(&shares, &ct).is_err());
+
+        // Relabeling the ciphertext for the other set invalidates it.
+        let mut relabeled = ct.clone();
+        relabeled.fingerprint = other_set.fingerprint();
+        assert!(!relabeled.verify(&other_set));
+        assert!(other_set
+            .decrypt_set_ciphertext(&shares, &relabeled)
+            .is_err());
+        assert!(sk_set
+            .secret_key_share(0)
+            .decrypt_share_with_aad(&ct.ciphertext, relabeled.fingerprint)
+            .is_err());
+    }
+
+    #[test]
+    fn quorum() {
+        let pk_set = SecretKeySet::random(3, &mut rand::thread_rng()).public_keys();
+        assert!(!pk_set.has_quorum(0));
+        assert!(!pk_set.has_quorum(3));
+        assert!(pk_set.has_quorum(4));
+        assert!(pk_set.has_quorum(10));
+        assert_eq!(4, pk_set.missing(0));
+        assert_eq!(1, pk_set.missing(3));
+        assert_eq!(0, pk_set.missing(4));
+        assert_eq!(0, pk_set.missing(10));
+
+        // A threshold of `0` needs a single share.
+        let pk_set = SecretKeySet::random(0, &mut rand::thread_rng()).public_keys();
+        assert!(!pk_set.has_quorum(0));
+        assert!(pk_set.has_quorum(1));
+        assert_eq!(1, pk_set.missing(0));
+    }
+
+    #[test]
+    fn empty_commitment() {
+        // An empty encoding, or one of only identity coefficients, decodes to an empty commitment.
+        let identities = Commitment {
+            coeff: vec![G1Projective::identity(); 2],
+        };
+        for bytes in [Vec::new(), identities.to_bytes()].iter() {
+            let pk_set = PublicKeySet::from(Commitment::from_bytes(bytes).unwrap());
+            assert_eq!(None, pk_set.public_key());
+            assert!(pk_set.encrypt(b"Rip and tear").is_err());
+        }
+    }
+
+    #[test]
+    fn combine_signatures_threshold() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+        let shares: BTreeMap<_, _> = (0..3)
+            .map(|i| (i, sk_set.secret_key_share(i).sign(msg)))
+            .collect();
+
+        // Exactly `threshold` shares are one too few.
+        let too_few = shares.iter().take(2).map(|(i, share)| (*i, share));
+        let err = pk_set.combine_signatures(too_few).unwrap_err();
+        assert_eq!("need t+1 = 3 shares, got 2", err.to_string());
+        let interpolator = Interpolator::new(&[0, 1]).unwrap();
+        let err = pk_set
+            .combine_signatures_with_coeffs(&interpolator, shares.values())
+            .unwrap_err();
+        assert_eq!("need t+1 = 3 shares, got 2", err.to_string());
+
+        // `threshold + 1` shares are enough.
+        let sig = pk_set.combine_signatures(&shares).unwrap();
+        assert!(pk_set.public_key().unwrap().verify(&sig, msg));
+    }
+
+    #[test]
+    fn decrypt_zeroizing_and_into() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(1, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+        let ct = pk_set.public_key().unwrap().encrypt(msg);
+        let shares: BTreeMap<_, _> = (0..2)
+            .map(|i| {
+                (
+                    i,
+                    sk_set.secret_key_share(i).try_decrypt_share(&ct).unwrap(),
+                )
+            })
+            .collect();
+        let expected = pk_set.decrypt(&shares, &ct).unwrap();
+        assert_eq!(msg.to_vec(), expected);
+        assert_eq!(expected, *pk_set.decrypt_zeroizing(&shares, &ct).unwrap());
+
+        let mut out = vec![0u8; 100];
+        let len = pk_set.decrypt_into(&shares, &ct, &mut out).unwrap();
+        assert_eq!(expected, out[..len]);
+
+        let mut short = [0u8; 8];
+        let err = pk_set.decrypt_into(&shares, &ct, &mut short).unwrap_err();
+        assert_eq!(
+            Some(&Error::BufferTooSmall(msg.len())),
+            err.downcast_ref::<Error>()
+        );
+    }
+
+    #[test]
+    fn combine_decryption_shares() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+        let ct = pk_set.public_key().unwrap().encrypt(msg);
+        let shares: BTreeMap<_, _> = (0..5)
+            .map(|i| {
+                (
+                    i,
+                    sk_set.secret_key_share(i).try_decrypt_share(&ct).unwrap(),
+                )
+            })
+            .collect();
+
+        let g = pk_set.combine_decryption_shares(&shares).unwrap();
+        let others = shares.iter().skip(2).map(|(i, share)| (*i, share));
+        assert_eq!(g, pk_set.combine_decryption_shares(others).unwrap());
+        let decrypted = pk_set.decrypt(&shares, &ct).unwrap();
+        assert_eq!(msg.to_vec(), decrypted);
+        assert_eq!(decrypted, xor_with_hash(g.into(), ct.v()));
+
+        let few = shares.iter().take(2).map(|(i, share)| (*i, share));
+        assert!(pk_set.combine_decryption_shares(few).is_err());
+    }
+
+    #[test]
+    fn decrypt_to_key() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let ct = pk_set
+            .public_key()
+            .unwrap()
+            .encrypt(b"Rip and tear, until it's done");
+        let shares: BTreeMap<_, _> = (0..5)
+            .map(|i| {
+                (
+                    i,
+                    sk_set.secret_key_share(i).try_decrypt_share(&ct).unwrap(),
+                )
+            })
+            .collect();
+
+        let key = pk_set.decrypt_to_key(&shares, &ct).unwrap();
+        assert_eq!(key, pk_set.decrypt_to_key(&shares, &ct).unwrap());
+        let others = shares.iter().skip(2).map(|(i, share)| (*i, share));
+        assert_eq!(key, pk_set.decrypt_to_key(others, &ct).unwrap());
+        // The key is not the seed of the payload's mask.
+        let g: G1Projective =
+            interpolate_at_zero(2, shares.iter().map(|(i, share)| (*i, &share.0))).unwrap();
+        assert_ne!(key, sha3_256(&g.to_affine().to_compressed()));
+
+        let other_ct = pk_set
+            .public_key()
+            .unwrap()
+            .encrypt(b"Rip and tear, until it's done");
+        let other_shares: BTreeMap<_, _> = (0..3)
+            .map(|i| {
+                (
+                    i,
+                    sk_set
+                        .secret_key_share(i)
+                        .try_decrypt_share(&other_ct)
+                        .unwrap(),
+                )
+            })
+            .collect();
+        assert_ne!(
+            key,
+            pk_set.decrypt_to_key(&other_shares, &other_ct).unwrap()
+        );
+        assert!(pk_set
+            .decrypt_to_key(shares.iter().take(2).map(|(i, share)| (*i, share)), &ct)
+            .is_err());
+    }
+
+    #[test]
+    fn reject_identity_shares() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+
+        let mut sig_shares: BTreeMap<_, _> = (0..3)
+            .map(|i| (i, sk_set.secret_key_share(i).sign(msg)))
+            .collect();
+        assert!(sig_shares.values().all(SignatureShare::is_valid));
+        assert!(pk_set.combine_signatures(&sig_shares).is_ok());
+        sig_shares.insert(1, SignatureShare(Signature(G2Projective::identity())));
+        let err = pk_set.combine_signatures(&sig_shares).unwrap_err();
+        assert_eq!(
+            Some(&Error::InvalidSignature(1)),
+            err.downcast_ref::<Error>()
+        );
+
+        let ct = pk_set.public_key().unwrap().encrypt(msg);
+        let mut dec_shares: BTreeMap<_, _> = (0..3)
+            .map(|i| {
+                (
+                    i,
+                    sk_set.secret_key_share(i).try_decrypt_share(&ct).unwrap(),
+                )
+            })
+            .collect();
+        assert!(dec_shares.values().all(DecryptionShare::is_valid));
+        assert_eq!(msg.to_vec(), pk_set.decrypt(&dec_shares, &ct).unwrap());
+        dec_shares.insert(2, DecryptionShare(G1Projective::identity()));
+        let err = pk_set.decrypt(&dec_shares, &ct).unwrap_err();
+        assert_eq!(
+            Some(&Error::InvalidDecryptionShare(2)),
+            err.downcast_ref::<Error>()
+        );
+
+        // The error has the share's position among the inputs, not its index.
+        let shifted = dec_shares.iter().map(|(i, share)| (i + 10, share));
+        let err = pk_set.decrypt(shifted, &ct).unwrap_err();
+        assert_eq!(
+            Some(&Error::InvalidDecryptionShare(2)),
+            err.downcast_ref::<Error>()
+        );
+    }
+
+    #[test]
+    fn try_combine() {
+        let mut rng = rand::thread_rng();
+        let sk_set1 = SecretKeySet::random(2, &mut rng);
+        let sk_set2 = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set1
+            .public_keys()
+            .try_combine(sk_set2.public_keys())
+            .expect("failed to combine");
+        assert_eq!(2, pk_set.threshold());
+        assert_eq!(sk_set1.public_keys().combine(sk_set2.public_keys()), pk_set);
+
+        let sk_set3 = SecretKeySet::random(3, &mut rng);
+        assert!(pk_set.try_combine(sk_set3.public_keys()).is_err());
+        assert_eq!(3, pk_set.combine(sk_set3.public_keys()).threshold());
+    }
+
+    #[test]
+    fn combine_signatures_with_coeffs() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let indices = [4, 1, 7];
+        let interpolator = Interpolator::new(&indices).unwrap();
+
+        for msg in [&b"Rip and tear"[..], &b"until it's done"[..]].iter() {
+            let shares: Vec<_> = indices
+                .iter()
+                .map(|i| sk_set.secret_key_share(*i).sign(msg))
+                .collect();
+            let sig = pk_set
+                .combine_signatures_with_coeffs(&interpolator, &shares)
+                .expect("failed to combine");
+            let by_index: BTreeMap<_, _> = indices.iter().zip(&shares).collect();
+            let expected = pk_set
+                .combine_signatures(by_index.iter().map(|(i, s)| (**i, *s)))
+                .unwrap();
+            assert_eq!(expected, sig);
+            assert!(pk_set.public_key().unwrap().verify(&sig, msg));
+        }
+
+        let shares: Vec<_> = indices
+            .iter()
+            .map(|i| sk_set.secret_key_share(*i).sign(b"msg"))
+            .collect();
+        assert!(pk_set
+            .combine_signatures_with_coeffs(&interpolator, &shares[..2])
+            .is_err());
+        let too_few = Interpolator::new(&indices[..2]).unwrap();
+        assert!(pk_set
+            .combine_signatures_with_coeffs(&too_few, &shares[..2])
+            .is_err());
+
+        // An identity share is rejected with its position, as in `combine_signatures`.
+        let mut shares = shares;
+        shares[1] = SignatureShare(Signature(G2Projective::identity()));
+        let err = pk_set
+            .combine_signatures_with_coeffs(&interpolator, &shares)
+            .unwrap_err();
+        assert_eq!(
+            Some(&Error::InvalidSignature(1)),
+            err.downcast_ref::<Error>()
+        );
     }
-    Ok(result)
 }
@@ -1,23 +1,124 @@
-use crate::util::hash_g1_g2;
-use crate::{Ciphertext, DecryptionShare, PublicKey, SignatureShare};
-use bls12_381::{pairing, G1Affine, G1Projective, G2Affine};
+use crate::util::{cmp_g1_projective, hash_g1_g2_aad};
+use crate::{Ciphertext, DecryptionShare, ProvenDecryptionShare, PublicKey, SignatureShare};
+use bls12_381::{multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared};
+use group::Group;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+/// A public key share.
+///
+/// Shares are ordered by their compressed encoding, so any set of them can be sorted into the
+/// same canonical order, e.g. for a roster.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct PublicKeyShare(pub PublicKey);
 
+impl Hash for PublicKeyShare {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bytes().as_ref().hash(state);
+    }
+}
+
+impl PartialOrd for PublicKeyShare {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PublicKeyShare {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_g1_projective(&(self.0).0, &(other.0).0)
+    }
+}
+
 impl PublicKeyShare {
     pub fn verify_decryption_share(&self, share: &DecryptionShare, ct: &Ciphertext) -> bool {
-        let Ciphertext(ref u, ref v, ref w) = *ct;
-        let hash = hash_g1_g2(*u, v);
-        pairing(&G1Affine::from(share.0), &G2Affine::from(hash))
-            == pairing(&G1Affine::from(self.0 .0), &G2Affine::from(w))
+        self.verify_decryption_share_with_aad(share, ct, [])
+    }
+
+    /// Returns `true` if `share` is this key's decryption share of `ct`, which was created with
+    /// the associated data `aad`.
+    pub fn verify_decryption_share_with_aad<A: AsRef<[u8]>>(
+        &self,
+        share: &DecryptionShare,
+        ct: &Ciphertext,
+        aad: A,
+    ) -> bool {
+        // `e(share, hash) == e(pk, w)`, with a single final exponentiation.
+        let hash = G2Prepared::from(G2Affine::from(hash_g1_g2_aad(
+            *ct.u(),
+            ct.v(),
+            aad.as_ref(),
+        )));
+        let w = G2Prepared::from(G2Affine::from(ct.w()));
+        let share = G1Affine::from(share.0);
+        let pk = G1Affine::from(self.0 .0);
+        let gt = multi_miller_loop(&[(&share, &hash), (&-pk, &w)]).final_exponentiation();
+        bool::from(gt.is_identity())
+    }
+
+    /// Returns `true` if `share` is this key's decryption share of `ct`, according to its proof.
+    ///
+    /// This costs four G1 multiplications instead of the two pairings of
+    /// `verify_decryption_share`. It doesn't check that `ct` is valid.
+    pub fn verify_proven_share(&self, share: &ProvenDecryptionShare, ct: &Ciphertext) -> bool {
+        share.verify(&(self.0).0, ct.u())
     }
 
     pub fn verify<M: AsRef<[u8]>>(&self, sig: &SignatureShare, msg: M) -> bool {
         self.0.verify(&sig.0, msg)
     }
 
+    /// Returns `true` if `sig` is this share's signature share of the message under the domain
+    /// separation tag `dst`, see `SecretKeyShare::sign_with_dst`.
+    pub fn verify_with_dst<M: AsRef<[u8]>>(
+        &self,
+        sig: &SignatureShare,
+        dst: &[u8],
+        msg: M,
+    ) -> bool {
+        self.0.verify_with_dst(&sig.0, dst, msg)
+    }
+
+    /// Returns `true` if `sig` is this share's signature share of the message with the given
+    /// `hash_g2`.
+    pub fn verify_hash(&self, sig: &SignatureShare, hash: &G2Affine) -> bool {
+        self.0.verify_hash(&sig.0, hash)
+    }
+
     pub fn combine(&self, other: &PublicKeyShare) -> PublicKeyShare {
         PublicKeyShare(PublicKey((self.0).0 + G1Projective::from((other.0).0)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretKeySet;
+    use std::collections::{BTreeMap, HashSet};
+
+    #[test]
+    fn ordering_and_hashing() {
+        let mut rng = rand::thread_rng();
+        let pk_set = SecretKeySet::random(2, &mut rng).public_keys();
+        let shares = pk_set.all_public_key_shares(10);
+
+        let entries: Vec<(PublicKeyShare, u64)> = shares.iter().copied().zip(0..).collect();
+        let roster: BTreeMap<PublicKeyShare, u64> = entries.iter().copied().collect();
+        let reversed: BTreeMap<PublicKeyShare, u64> = entries.iter().rev().copied().collect();
+        // The iteration order doesn't depend on the insertion order, and is that of the encodings.
+        assert!(roster.iter().eq(reversed.iter()));
+        let encodings: Vec<_> = roster.keys().map(|pk| pk.0.to_bytes()).collect();
+        assert!(encodings.windows(2).all(|w| w[0] < w[1]));
+        for (i, pk) in shares.iter().enumerate() {
+            assert_eq!(Some(&(i as u64)), roster.get(pk));
+        }
+
+        // The same point in other projective coordinates is the same key.
+        let share = shares[3];
+        let g = G1Projective::generator();
+        let other = PublicKeyShare(PublicKey((share.0).0 + g - g));
+        assert_eq!(Ordering::Equal, share.cmp(&other));
+        let set: HashSet<PublicKeyShare> = shares.iter().copied().chain(Some(other)).collect();
+        assert_eq!(10, set.len());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::util::{clear_scalar, coeff_pos};
+use crate::util::{clear_scalar, coeff_pos, Redacted};
 use crate::{Commitment, IntoScalar};
 use anyhow::{bail, Result};
 use bls12_381::{G1Affine, G1Projective, Scalar};
@@ -6,16 +6,32 @@ use ff::Field;
 use rand::Rng;
 use rand_core::RngCore;
 use std::borrow::Borrow;
+use std::fmt;
 use std::iter;
 use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use zeroize::Zeroize;
 
-#[derive(PartialEq, Eq, Clone, Debug)]
+/// A univariate polynomial in the prime field.
+///
+/// Its `Debug` output only shows the degree, unless the `debug-secrets` feature is enabled.
+#[derive(PartialEq, Eq, Clone)]
 pub struct Poly {
     /// The coefficients of a polynomial.
     pub coeff: Vec<Scalar>,
 }
 
+impl fmt::Debug for Poly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = f.debug_struct("Poly");
+        s.field("degree", &self.degree());
+        if cfg!(feature = "debug-secrets") {
+            s.field("coeff", &self.coeff).finish()
+        } else {
+            s.field("coeff", &Redacted).finish()
+        }
+    }
+}
+
 impl Zeroize for Poly {
     fn zeroize(&mut self) {
         for scalar in self.coeff.iter_mut() {
@@ -90,6 +106,25 @@ impl Poly {
         Ok(Poly::from(coeff))
     }
 
+    /// Returns `true` if the leading coefficient, i.e. the last one that isn't zero, is `1`.
+    ///
+    /// The zero polynomial has no leading coefficient, so it is not monic.
+    pub fn is_monic(&self) -> bool {
+        self.coeff.iter().rev().find(|c| !c.is_zero()) == Some(&Scalar::one())
+    }
+
+    /// Divides the polynomial by its leading coefficient, making it monic, and removes trailing
+    /// zero coefficients. Fails for the zero polynomial.
+    pub fn make_monic(&mut self) -> Result<()> {
+        self.remove_zeros();
+        let lead = match self.coeff.last() {
+            Some(lead) => lead.invert().unwrap(),
+            None => bail!("The zero polynomial cannot be made monic"),
+        };
+        *self *= lead;
+        Ok(())
+    }
+
     /// Removes all trailing zero coefficients.
     fn remove_zeros(&mut self) {
         let zeros = self.coeff.iter().rev().take_while(|c| c.is_zero()).count();
@@ -111,6 +146,19 @@ impl Poly {
         result
     }
 
+    /// Returns the elements of `domain` at which the polynomial is zero, in order.
+    ///
+    /// This simply evaluates the polynomial at every point of the domain, so it is exact, but it
+    /// only finds roots within the domain; roots elsewhere in the field are not reported. The zero
+    /// polynomial vanishes everywhere, so for it the whole domain is returned.
+    pub fn roots_in(&self, domain: &[Scalar]) -> Vec<Scalar> {
+        domain
+            .iter()
+            .filter(|x| self.evaluate(**x).is_zero())
+            .copied()
+            .collect()
+    }
+
     /// Returns the unique polynomial `f` of degree `samples.len() - 1` with the given values
     /// `(x, f(x))`.
     pub fn interpolate<T, U, I>(samples_repr: I) -> Self
@@ -368,6 +416,36 @@ mod tests {
     use super::*;
     use rand::{thread_rng, Rng};
 
+    #[test]
+    fn monic() {
+        assert!(Poly::one().is_monic());
+        assert!(Poly::monomial(3).is_monic());
+        assert!(Poly::from(vec![Scalar::from(5), Scalar::one(), Scalar::zero()]).is_monic());
+        assert!(!Poly::zero().is_monic());
+        assert!(!Poly::from(vec![Scalar::zero()]).is_monic());
+
+        // 2x + 2 becomes x + 1.
+        let mut poly = Poly::from(vec![Scalar::from(2), Scalar::from(2), Scalar::zero()]);
+        assert!(!poly.is_monic());
+        poly.make_monic().unwrap();
+        assert_eq!(Poly::from(vec![Scalar::one(), Scalar::one()]), poly);
+        assert!(poly.is_monic());
+
+        let mut rng = thread_rng();
+        let mut random = Poly::random(4, &mut rng);
+        let roots = [Scalar::from(3), Scalar::from(7)];
+        random *= Poly::from(vec![-roots[0], Scalar::one()]);
+        random *= Poly::from(vec![-roots[1], Scalar::one()]);
+        random.make_monic().unwrap();
+        assert!(random.is_monic());
+        assert_eq!(6, random.degree());
+        assert_eq!(roots.to_vec(), random.roots_in(&roots));
+
+        let mut zero = Poly::from(vec![Scalar::zero(); 3]);
+        assert!(zero.make_monic().is_err());
+        assert!(Poly::zero().make_monic().is_err());
+    }
+
     #[test]
     fn rand_degree() {
         let deg = 2;
@@ -439,6 +517,23 @@ mod tests {
         assert_eq!(interp, p1);
     }
 
+    #[test]
+    fn roots_in() {
+        let roots: Vec<Scalar> = [2u64, 5, 11].iter().map(|r| r.into_scalar()).collect();
+        let mut poly = Poly::one();
+        for r in &roots {
+            poly *= Poly::from(vec![-*r, Scalar::one()]);
+        }
+        assert_eq!(3, poly.degree());
+
+        let domain: Vec<Scalar> = (0u64..16).map(IntoScalar::into_scalar).collect();
+        assert_eq!(roots, poly.roots_in(&domain));
+        assert_eq!(vec![roots[1]], poly.roots_in(&domain[3..8]));
+        assert!(poly.roots_in(&[]).is_empty());
+        assert!(Poly::one().roots_in(&domain).is_empty());
+        assert_eq!(domain, Poly::zero().roots_in(&domain));
+    }
+
     #[test]
     fn zeroize() {
         let mut poly = Poly::monomial(3) + Poly::monomial(2) - 1;
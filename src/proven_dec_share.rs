@@ -0,0 +1,150 @@
+use crate::util::{clear_scalar, hash_to_scalar};
+use crate::DecryptionShare;
+use bls12_381::{G1Affine, G1Projective, Scalar};
+use ff::Field;
+use group::Curve;
+use rand::RngCore;
+
+/// The domain separation tag of the proof's challenge.
+const DLEQ_DST: &[u8] = b"RUST_TC_DLEQ_V1_";
+
+/// A decryption share with a proof that it was computed with a particular key share.
+///
+/// The proof is a Chaum–Pedersen proof of equality of discrete logarithms: the share is `u * sk_i`
+/// for the same `sk_i` as in `pk_i = g1 * sk_i`. It is checked with four G1 multiplications and
+/// no pairings, see `PublicKeyShare::verify_proven_share`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ProvenDecryptionShare {
+    share: DecryptionShare,
+    challenge: Scalar,
+    response: Scalar,
+}
+
+impl ProvenDecryptionShare {
+    /// Computes the share of `u` with the key share `sk`, whose public key share is `pk`, and
+    /// proves it using randomness from `rng`.
+    pub(crate) fn new<R: RngCore>(
+        rng: &mut R,
+        sk: &Scalar,
+        pk: &G1Projective,
+        u: &G1Projective,
+    ) -> Self {
+        let share = u * sk;
+        let mut k = Scalar::random(rng);
+        let challenge = challenge(pk, u, &share, &(G1Affine::generator() * k), &(u * k));
+        let response = k + challenge * sk;
+        clear_scalar(&mut k);
+        ProvenDecryptionShare {
+            share: DecryptionShare(share),
+            challenge,
+            response,
+        }
+    }
+
+    /// Returns `true` if the proof shows that this is the share of `u` for the public key share
+    /// `pk`.
+    pub(crate) fn verify(&self, pk: &G1Projective, u: &G1Projective) -> bool {
+        if !self.share.is_valid() {
+            return false;
+        }
+        let share = &self.share.0;
+        // The prover's commitments are `g1 * k` and `u * k`, with `k = response - challenge * sk`.
+        let a1 = G1Affine::generator() * self.response - pk * self.challenge;
+        let a2 = u * self.response - share * self.challenge;
+        challenge(pk, u, share, &a1, &a2) == self.challenge
+    }
+
+    /// Returns the decryption share, e.g. to combine it with `PublicKeySet::decrypt`.
+    pub fn share(&self) -> &DecryptionShare {
+        &self.share
+    }
+
+    /// Returns the decryption share, discarding the proof.
+    pub fn into_share(self) -> DecryptionShare {
+        self.share
+    }
+}
+
+/// Returns the Fiat–Shamir challenge for the statement `(pk, u, share)` and commitments `(a1, a2)`.
+fn challenge(
+    pk: &G1Projective,
+    u: &G1Projective,
+    share: &G1Projective,
+    a1: &G1Projective,
+    a2: &G1Projective,
+) -> Scalar {
+    let mut input = Vec::with_capacity(5 * 48);
+    for p in [pk, u, share, a1, a2] {
+        input.extend_from_slice(&p.to_affine().to_compressed());
+    }
+    hash_to_scalar(&input, DLEQ_DST)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ciphertext, SecretKeySet};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn proven_shares() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+        let ct = pk_set.public_key().unwrap().encrypt(msg);
+        let other_ct = pk_set.public_key().unwrap().encrypt(msg);
+
+        let shares: BTreeMap<_, _> = (0..4)
+            .map(|i| {
+                let sk_share = sk_set.secret_key_share(i);
+                (i, sk_share.decrypt_share_proven(&ct, &mut rng).unwrap())
+            })
+            .collect();
+        for (i, share) in &shares {
+            let pk_share = pk_set.public_key_share(i);
+            assert!(pk_share.verify_proven_share(share, &ct));
+            assert!(pk_share.verify_decryption_share(share.share(), &ct));
+            // The proof is bound to the ciphertext and the key share.
+            assert!(!pk_share.verify_proven_share(share, &other_ct));
+            assert!(!pk_set
+                .public_key_share(i + 1)
+                .verify_proven_share(share, &ct));
+        }
+
+        let decrypted = pk_set
+            .decrypt(shares.iter().map(|(i, share)| (*i, share.share())), &ct)
+            .unwrap();
+        assert_eq!(msg.to_vec(), decrypted);
+
+        // A share for the other ciphertext with a proof copied from a valid share.
+        let pk_share = pk_set.public_key_share(0);
+        let wrong = sk_set
+            .secret_key_share(0)
+            .try_decrypt_share(&other_ct)
+            .unwrap();
+        let forged = ProvenDecryptionShare {
+            share: wrong,
+            ..shares[&0].clone()
+        };
+        assert!(!pk_share.verify_proven_share(&forged, &ct));
+        assert!(!pk_share.verify_proven_share(&forged, &other_ct));
+
+        // Changing the response or the challenge invalidates the proof.
+        let mut forged = shares[&0].clone();
+        forged.response += Scalar::one();
+        assert!(!pk_share.verify_proven_share(&forged, &ct));
+        let mut forged = shares[&0].clone();
+        forged.challenge += Scalar::one();
+        assert!(!pk_share.verify_proven_share(&forged, &ct));
+
+        // Invalid ciphertexts are rejected before computing a share.
+        let mut v = ct.v().to_vec();
+        v[0] ^= 1;
+        let invalid = Ciphertext::from_parts(*ct.u(), v, *ct.w());
+        assert!(sk_set
+            .secret_key_share(0)
+            .decrypt_share_proven(&invalid, &mut rng)
+            .is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use crate::Ciphertext;
+use bls12_381::G1Projective;
+
+/// A ciphertext that has been re-encrypted to a new public key by a threshold group.
+///
+/// It carries the original ciphertext together with an ElGamal encryption, under the target
+/// public key, of the group element `u * sk` needed to decrypt it. Only the holder of the target
+/// secret key can recover that element and thereby the plaintext.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReEncryptedCiphertext(pub Ciphertext, pub G1Projective, pub G1Projective);
+
+impl ReEncryptedCiphertext {
+    /// Returns `true` if the underlying ciphertext is valid.
+    pub fn verify(&self) -> bool {
+        self.0.verify()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SecretKey, SecretKeySet};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn reencrypt_to_target() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let target = SecretKey::random();
+
+        let msg = b"Rip and tear, until it's done";
+        let ct = pk_set.public_key().unwrap().encrypt(msg);
+
+        let shares: BTreeMap<_, _> = [0, 2, 4]
+            .iter()
+            .map(|&i| {
+                let share = sk_set
+                    .secret_key_share(i)
+                    .reencrypt_share(&ct, &target.public_key())
+                    .expect("ciphertext should be valid");
+                (i, share)
+            })
+            .collect();
+        let reenc = pk_set
+            .combine_reencryption_shares(&shares, &ct)
+            .expect("failed to combine re-encryption shares");
+
+        assert_eq!(Some(msg.to_vec()), target.decrypt_reencrypted(&reenc));
+        let other = SecretKey::random();
+        assert_ne!(Some(msg.to_vec()), other.decrypt_reencrypted(&reenc));
+    }
+
+    #[test]
+    fn not_enough_shares() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let target = SecretKey::random();
+        let ct = pk_set.public_key().unwrap().encrypt(b"Rip and tear");
+
+        let shares: BTreeMap<_, _> = (0..2)
+            .map(|i| {
+                let share = sk_set
+                    .secret_key_share(i)
+                    .reencrypt_share(&ct, &target.public_key())
+                    .unwrap();
+                (i, share)
+            })
+            .collect();
+        assert!(pk_set.combine_reencryption_shares(&shares, &ct).is_err());
+    }
+}
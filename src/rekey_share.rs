@@ -0,0 +1,8 @@
+use bls12_381::G1Projective;
+
+/// A share of a threshold re-encryption.
+///
+/// The first element is the node's decryption share masked with a random multiple of the target
+/// public key, `u * sk_i + target * rho_i`; the second is the mask's commitment `g * rho_i`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReKeyShare(pub G1Projective, pub G1Projective);
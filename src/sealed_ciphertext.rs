@@ -0,0 +1,139 @@
+use crate::{Ciphertext, Error};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+
+/// A message encrypted with a hybrid scheme, for payloads too large for `Ciphertext`.
+///
+/// The `header` encrypts a fresh random 32-byte key to the public key, and the `payload` is the
+/// message encrypted and authenticated with XChaCha20-Poly1305 under that key. The header is
+/// small and decrypted like any other ciphertext, including by threshold decryption; the payload
+/// is only ever opened with the symmetric key, and any change to it is detected.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SealedCiphertext {
+    /// The encrypted symmetric key.
+    pub header: Ciphertext,
+    /// The encrypted message and its authentication tag.
+    pub payload: Vec<u8>,
+}
+
+/// Encrypts `msg` under the single-use `key`.
+///
+/// Every key encrypts exactly one payload, so a fixed nonce is safe.
+pub(crate) fn seal_payload(key: &[u8; 32], msg: &[u8]) -> Vec<u8> {
+    XChaCha20Poly1305::new(Key::from_slice(key))
+        .encrypt(&XNonce::default(), msg)
+        .expect("message too long to seal")
+}
+
+/// Decrypts a payload sealed with `seal_payload`, or returns `Error::InvalidCiphertext` if the key
+/// is wrong or the payload was tampered with.
+pub(crate) fn open_payload(key: &[u8], payload: &[u8]) -> Result<Vec<u8>, Error> {
+    if key.len() != 32 {
+        return Err(Error::InvalidCiphertext);
+    }
+    XChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(&XNonce::default(), payload)
+        .map_err(|_| Error::InvalidCiphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SecretKey, SecretKeySet};
+    use rand::RngCore;
+    use std::collections::BTreeMap;
+
+    fn large_msg() -> Vec<u8> {
+        let mut msg = vec![0u8; 10 * 1024 * 1024];
+        rand::thread_rng().fill_bytes(&mut msg);
+        msg
+    }
+
+    #[test]
+    fn round_trip() {
+        let sk = SecretKey::random();
+        let msg = large_msg();
+        let ct = sk.public_key().encrypt_sealed(&msg);
+        assert!(ct.header.verify());
+        assert_eq!(msg, sk.decrypt_sealed(&ct).expect("failed to open"));
+
+        let ct = sk.public_key().encrypt_sealed(b"");
+        assert_eq!(
+            Vec::<u8>::new(),
+            sk.decrypt_sealed(&ct).expect("failed to open")
+        );
+    }
+
+    #[test]
+    fn threshold_round_trip() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = large_msg();
+        let ct = pk_set.public_key().unwrap().encrypt_sealed(&msg);
+
+        let shares: BTreeMap<_, _> = (0..3)
+            .map(|i| {
+                let share = sk_set.secret_key_share(i);
+                (i, share.try_decrypt_share(&ct.header).unwrap())
+            })
+            .collect();
+        let opened = pk_set.decrypt_sealed(&shares, &ct).expect("failed to open");
+        assert_eq!(msg, opened);
+        assert!(pk_set.decrypt_sealed(shares.iter().take(2), &ct).is_err());
+    }
+
+    /// Returns copies of `ct` with a modified payload, or with another ciphertext's header.
+    fn tampered(ct: &SealedCiphertext, other: &SealedCiphertext) -> Vec<SealedCiphertext> {
+        let mut flipped = ct.clone();
+        flipped.payload[3] ^= 1;
+        let mut truncated = ct.clone();
+        truncated.payload.pop();
+        let mut extended = ct.clone();
+        extended.payload.push(0);
+        let mut swapped = ct.clone();
+        swapped.header = other.header.clone();
+        vec![flipped, truncated, extended, swapped]
+    }
+
+    #[test]
+    fn tampered_payload() {
+        let sk = SecretKey::random();
+        let msg = b"Rip and tear, until it's done";
+        let ct = sk.public_key().encrypt_sealed(msg);
+        let other = sk.public_key().encrypt_sealed(msg);
+        for bad in tampered(&ct, &other) {
+            assert_eq!(Err(Error::InvalidCiphertext), sk.decrypt_sealed(&bad));
+        }
+
+        let mut bad_header = ct;
+        let header = &bad_header.header;
+        let mut v = header.v().to_vec();
+        v[0] ^= 1;
+        bad_header.header = Ciphertext::from_parts(*header.u(), v, *header.w());
+        assert_eq!(
+            Err(Error::InvalidCiphertext),
+            sk.decrypt_sealed(&bad_header)
+        );
+    }
+
+    #[test]
+    fn threshold_tampered_payload() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(1, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+        let ct = pk_set.public_key().unwrap().encrypt_sealed(msg);
+        let other = pk_set.public_key().unwrap().encrypt_sealed(msg);
+        let shares: BTreeMap<_, _> = (0..2)
+            .map(|i| {
+                let share = sk_set.secret_key_share(i);
+                (i, share.try_decrypt_share(&ct.header).unwrap())
+            })
+            .collect();
+        assert!(pk_set.decrypt_sealed(&shares, &ct).is_ok());
+        for bad in tampered(&ct, &other) {
+            assert!(pk_set.decrypt_sealed(&shares, &bad).is_err());
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{Ciphertext, PublicKeySet};
+
+/// A ciphertext encrypted to the master key of a `PublicKeySet`, tagged with the set's
+/// fingerprint.
+///
+/// The fingerprint is bound to the ciphertext as associated data, so it can't be replaced to
+/// redirect the ciphertext to another set. Share holders decrypt it with
+/// `SecretKeyShare::decrypt_share_with_aad`, passing the fingerprint as the associated data, and
+/// the shares are combined with `PublicKeySet::decrypt_set_ciphertext`.
+#[derive(Clone, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
+pub struct SetCiphertext {
+    /// The fingerprint of the public key set the ciphertext is meant for.
+    pub fingerprint: [u8; 32],
+    /// The encrypted message, with the fingerprint as associated data.
+    pub ciphertext: Ciphertext,
+}
+
+impl SetCiphertext {
+    /// Returns `true` if the ciphertext is valid and meant for `pk_set`.
+    pub fn verify(&self, pk_set: &PublicKeySet) -> bool {
+        self.fingerprint == pk_set.fingerprint()
+            && self.ciphertext.verify_with_aad(self.fingerprint)
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{PublicKeySet, Signature, SignatureShare};
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+/// A set of signature shares, keyed by the index of the node that produced them.
+///
+/// Collecting `(index, share)` pairs into a `ShareCollection` dedupes indices: if the same index
+/// appears more than once, the last share wins.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShareCollection {
+    shares: BTreeMap<usize, SignatureShare>,
+}
+
+impl FromIterator<(usize, SignatureShare)> for ShareCollection {
+    fn from_iter<I: IntoIterator<Item = (usize, SignatureShare)>>(iter: I) -> Self {
+        ShareCollection {
+            shares: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl ShareCollection {
+    /// Returns the number of distinct indices in the collection.
+    pub fn len(&self) -> usize {
+        self.shares.len()
+    }
+
+    /// Returns `true` if the collection contains no shares.
+    pub fn is_empty(&self) -> bool {
+        self.shares.is_empty()
+    }
+
+    /// Combines the collected shares into a full signature.
+    pub fn combine(&self, pk_set: &PublicKeySet) -> Result<Signature> {
+        pk_set.combine_signatures(&self.shares)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretKeySet;
+
+    #[test]
+    fn collect_and_combine() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+
+        let shares: Vec<_> = (0..5)
+            .map(|i| (i, sk_set.secret_key_share(i).sign(msg)))
+            .collect();
+        let collection: ShareCollection = shares.into_iter().collect();
+        assert_eq!(5, collection.len());
+
+        let sig = collection
+            .combine(&pk_set)
+            .expect("failed to combine shares");
+        assert!(pk_set.public_key().unwrap().verify(&sig, msg));
+    }
+
+    #[test]
+    fn dedupe_last_wins() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(1, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let msg = b"Rip and tear, until it's done";
+
+        // The bogus share for index 0 is replaced by the correct one that follows it.
+        let bogus = sk_set.secret_key_share(0).sign(b"other msg");
+        let shares = vec![
+            (0, bogus),
+            (0, sk_set.secret_key_share(0).sign(msg)),
+            (1, sk_set.secret_key_share(1).sign(msg)),
+        ];
+        let collection: ShareCollection = shares.into_iter().collect();
+        assert_eq!(2, collection.len());
+
+        let sig = collection
+            .combine(&pk_set)
+            .expect("failed to combine shares");
+        assert!(pk_set.public_key().unwrap().verify(&sig, msg));
+    }
+}
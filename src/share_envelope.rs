@@ -0,0 +1,105 @@
+use crate::util::sha3_256;
+use crate::{PublicKeySet, SecretKeyShare, SignatureShare};
+use serde::{Deserialize, Serialize};
+
+/// A self-describing signature share, for broadcasting to the other signers.
+///
+/// Besides the share, it names the public key set and the share's index, and contains the
+/// SHA3-256 hash of the signed message, so a receiver can check it against the message they
+/// expect. Its serde encoding is the wire format: the index is a `u64` on all platforms.
+#[derive(Clone, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
+pub struct ShareEnvelope {
+    /// The fingerprint of the public key set, see `PublicKeySet::fingerprint`.
+    pub set_fingerprint: [u8; 32],
+    /// The index of the secret key share that signed.
+    pub index: u64,
+    /// The SHA3-256 hash of the signed message.
+    pub msg_hash: [u8; 32],
+    /// The signature share.
+    pub share: SignatureShare,
+}
+
+impl ShareEnvelope {
+    /// Signs `msg` with the `index`-th secret key share of `pk_set`.
+    pub fn new<M: AsRef<[u8]>>(
+        pk_set: &PublicKeySet,
+        index: usize,
+        sk_share: &SecretKeyShare,
+        msg: M,
+    ) -> Self {
+        ShareEnvelope {
+            set_fingerprint: pk_set.fingerprint(),
+            index: index as u64,
+            msg_hash: sha3_256(msg.as_ref()),
+            share: sk_share.sign(msg),
+        }
+    }
+
+    /// Returns `true` if the envelope is for `pk_set` and `msg`, and contains a valid signature
+    /// share by its index.
+    pub fn verify<M: AsRef<[u8]>>(&self, pk_set: &PublicKeySet, msg: M) -> bool {
+        let index = self.index as usize;
+        self.set_fingerprint == pk_set.fingerprint()
+            && self.index == index as u64
+            && self.msg_hash == sha3_256(msg.as_ref())
+            && pk_set.public_key_share(index).verify(&self.share, msg)
+    }
+
+    /// Returns the index and the signature share, e.g. to combine with
+    /// `PublicKeySet::combine_signatures`.
+    pub fn into_share(self) -> (usize, SignatureShare) {
+        (self.index as usize, self.share)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretKeySet;
+
+    #[test]
+    fn serde_round_trip() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let envelope = sk_set.share_envelope(3, b"Rip and tear, until it's done");
+        let bytes = bincode::serialize(&envelope).expect("failed to serialize");
+        let deser: ShareEnvelope = bincode::deserialize(&bytes).expect("failed to deserialize");
+        assert_eq!(envelope, deser);
+        assert!(bincode::deserialize::<ShareEnvelope>(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn verify() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let other_set = SecretKeySet::random(2, &mut rng).public_keys();
+        let msg = b"Rip and tear, until it's done";
+
+        let envelopes: Vec<_> = (0..3)
+            .map(|i| ShareEnvelope::new(&pk_set, i, &sk_set.secret_key_share(i), msg))
+            .collect();
+        for envelope in &envelopes {
+            assert!(envelope.verify(&pk_set, msg));
+            assert!(!envelope.verify(&other_set, msg));
+            assert!(!envelope.verify(&pk_set, b"Other msg"));
+        }
+        assert_eq!(envelopes[1], sk_set.share_envelope(1, msg));
+
+        let mut wrong_index = envelopes[0].clone();
+        wrong_index.index = 1;
+        assert!(!wrong_index.verify(&pk_set, msg));
+        let mut out_of_range = envelopes[0].clone();
+        out_of_range.index = u64::MAX;
+        assert!(!out_of_range.verify(&pk_set, msg));
+
+        let shares: Vec<_> = envelopes
+            .into_iter()
+            .map(ShareEnvelope::into_share)
+            .collect();
+        let sig = pk_set
+            .combine_signatures(shares.iter().map(|(i, share)| (*i, share)))
+            .expect("failed to combine");
+        assert!(pk_set.public_key().unwrap().verify(&sig, msg));
+    }
+}
@@ -1,24 +1,247 @@
-use crate::{pk::PublicKey, util::hash_g2};
+use crate::pk::{aggregate_public_keys, PublicKey};
+use crate::util::{self, cmp_g2_projective, hash_g2, hash_g2_with_dst, sha3_256};
+use crate::Error;
 use anyhow::{bail, Result};
 use bls12_381::{
-    multi_miller_loop, pairing, G1Affine, G2Affine, G2Prepared, G2Projective, Gt, MillerLoopResult,
-    Scalar,
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Scalar,
 };
-use group::Curve;
+use group::{Curve, Group};
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::fmt;
-use std::ops::{AddAssign, Mul};
+use std::hash::{Hash, Hasher};
+use std::ops::AddAssign;
+use tiny_keccak::{Hasher as _, Shake};
 
 const SIGSIZE: usize = 96;
 
+/// The size of an uncompressed signature.
+const SIG_UNCOMPRESSED_SIZE: usize = 192;
+
+/// The size of a base field element in the Ethereum (EIP-2537) encoding: 48 big-endian bytes,
+/// left-padded with zeros.
+const ETH_FP_SIZE: usize = 64;
+
+/// The size of a signature in the Ethereum (EIP-2537) encoding.
+pub const ETH_SIG_SIZE: usize = 4 * ETH_FP_SIZE;
+
+/// The domain tag of `Signature::to_random_bytes`.
+const RANDOM_BYTES_TAG: &[u8] = b"RUST_TC_SIG_RANDOM_BYTES_V1";
+
+/// The domain tag of `Signature::to_scalar`.
+const SCALAR_TAG: &[u8] = b"RUST_TC_SIG_SCALAR_V1";
+
 #[derive(Clone, PartialEq, Eq, Debug, Copy)]
 pub struct Signature(pub G2Projective);
 
 impl Signature {
+    /// Returns `true` if the signature is a point in the G2 subgroup other than the identity.
+    ///
+    /// The identity is the signature of every message by the zero key, so it is never valid.
     pub fn is_valid(&self) -> bool {
-        self.0.to_affine().to_compressed().len() == SIGSIZE
+        let p: G2Affine = self.0.to_affine();
+        !bool::from(p.is_identity()) && bool::from(p.is_on_curve() & p.is_torsion_free())
+    }
+
+    /// Returns a bit determined by the signature, e.g. for a common coin.
+    ///
+    /// This is the lowest bit of `to_u64`, so it is unbiased and the same on all platforms.
+    pub fn parity(&self) -> bool {
+        self.to_u64() & 1 == 1
+    }
+
+    /// Returns a number determined by the signature: the first 8 bytes of the SHA3-256 hash of the
+    /// compressed encoding, in big-endian order.
+    pub fn to_u64(&self) -> u64 {
+        let digest = sha3_256(&self.to_bytes());
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        u64::from_be_bytes(bytes)
+    }
+
+    /// Returns `N` pseudorandom bytes determined by the signature, e.g. for a randomness beacon.
+    ///
+    /// This is SHAKE256 of a length-prefixed domain tag followed by the compressed encoding, so it
+    /// is the same on all platforms and for deserialized copies. The output is unpredictable
+    /// without the signature, and unique because a BLS signature of a given message and key is.
+    pub fn to_random_bytes<const N: usize>(&self) -> [u8; N] {
+        let mut out = [0u8; N];
+        self.xof(RANDOM_BYTES_TAG, &mut out);
+        out
+    }
+
+    /// Returns a pseudorandom scalar determined by the signature, see `to_random_bytes`.
+    ///
+    /// 64 bytes of output under another domain tag are read as a little-endian number and reduced
+    /// modulo the group order, so the result is statistically close to uniform.
+    pub fn to_scalar(&self) -> Scalar {
+        let mut wide = [0u8; 64];
+        self.xof(SCALAR_TAG, &mut wide);
+        Scalar::from_bytes_wide(&wide)
+    }
+
+    fn xof(&self, tag: &[u8], out: &mut [u8]) {
+        let mut shake = Shake::v256();
+        shake.update(&[tag.len() as u8]);
+        shake.update(tag);
+        shake.update(&self.to_bytes());
+        shake.finalize(out);
+    }
+
+    /// Returns the aggregate with `other`'s contribution removed, i.e. the difference of the points.
+    ///
+    /// This doesn't check that `other` was part of the aggregate; if it wasn't, the result is not
+    /// a valid signature of anything. See `deaggregate` for a checked version.
+    pub fn remove(&self, other: &Signature) -> Signature {
+        Signature(self.0 - other.0)
+    }
+
+    /// Brings the signature into its canonical representation, in constant time.
+    ///
+    /// A point has many projective representations `(X : Y : Z)`; this rescales it to `Z = 1`, or
+    /// to `(0 : 1 : 0)` for the identity, so equal signatures have equal coordinates. Encodings
+    /// are unaffected: they are always computed from the affine point.
+    ///
+    /// BLS signatures are not malleable by negation, so no sign is chosen here. The compressed
+    /// encoding includes the sign of `y`, so a point and its negation encode differently, and
+    /// `-sig` is never a valid signature of a message that `sig` is valid for, unless `sig` is the
+    /// identity. Both are unique, so there is no second valid representation to canonicalize.
+    pub fn normalize(&mut self) {
+        self.0 = G2Projective::from(self.0.to_affine());
+    }
+
+    /// Returns the compressed encoding of the signature.
+    pub fn to_bytes(&self) -> [u8; SIGSIZE] {
+        self.0.to_affine().to_compressed()
+    }
+
+    /// Decodes a signature from its compressed encoding.
+    ///
+    /// Fails if the length is wrong or the bytes aren't a point in the G2 subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes: &[u8; SIGSIZE] = match bytes.try_into() {
+            Ok(bytes) => bytes,
+            Err(_) => bail!(
+                "Invalid signature length {}, expected {}",
+                bytes.len(),
+                SIGSIZE
+            ),
+        };
+        match Option::<G2Affine>::from(G2Affine::from_compressed(bytes)) {
+            Some(affine) => Ok(Signature(affine.into())),
+            None => bail!("Invalid signature encoding"),
+        }
+    }
+
+    /// Returns the uncompressed encoding of the signature, with both coordinates.
+    ///
+    /// This is twice the size of `to_bytes`, for systems that store or expect uncompressed points.
+    pub fn to_bytes_uncompressed(&self) -> [u8; SIG_UNCOMPRESSED_SIZE] {
+        self.0.to_affine().to_uncompressed()
+    }
+
+    /// Decodes a signature from its uncompressed encoding.
+    ///
+    /// Fails if the length is wrong or the bytes aren't a point in the G2 subgroup.
+    pub fn from_bytes_uncompressed(bytes: &[u8]) -> Result<Self> {
+        let bytes: &[u8; SIG_UNCOMPRESSED_SIZE] = match bytes.try_into() {
+            Ok(bytes) => bytes,
+            Err(_) => bail!(
+                "Invalid uncompressed signature length {}, expected {}",
+                bytes.len(),
+                SIG_UNCOMPRESSED_SIZE
+            ),
+        };
+        match Option::<G2Affine>::from(G2Affine::from_uncompressed(bytes)) {
+            Some(affine) => Ok(Signature(affine.into())),
+            None => bail!("Invalid uncompressed signature encoding"),
+        }
+    }
+
+    /// Returns the compressed encoding as a `0x`-prefixed hex string, the format eth2 tooling
+    /// uses for BLS signatures.
+    ///
+    /// Unlike `to_eth_bytes`, this is the 96 byte flagged encoding of `to_bytes`, not EIP-2537.
+    pub fn to_eth_hex(&self) -> String {
+        util::to_eth_hex(&self.to_bytes())
+    }
+
+    /// Decodes a signature from a `0x`-prefixed hex string, see `to_eth_hex`.
+    ///
+    /// The same checks as in `from_bytes` apply, so the infinity encoding is accepted.
+    pub fn from_eth_hex(hex: &str) -> Result<Self> {
+        Signature::from_bytes(&util::from_eth_hex(hex)?)
+    }
+
+    /// Returns the signature in the uncompressed G2 encoding of Ethereum's BLS12-381 precompiles
+    /// (EIP-2537).
+    ///
+    /// That is `x.c0 || x.c1 || y.c0 || y.c1`, each coordinate a 64 byte big-endian number, with
+    /// no flag bits. The point at infinity is all zeros.
+    pub fn to_eth_bytes(&self) -> [u8; ETH_SIG_SIZE] {
+        let mut out = [0u8; ETH_SIG_SIZE];
+        let affine = self.0.to_affine();
+        if bool::from(affine.is_identity()) {
+            return out;
+        }
+        // The zkcrypto encoding is `x.c1 || x.c0 || y.c1 || y.c0`, 48 bytes each, and its flag
+        // bits are all clear for an uncompressed point that isn't the identity.
+        let raw = affine.to_uncompressed();
+        for (i, fp) in raw.chunks(48).enumerate() {
+            let pos = (i ^ 1) * ETH_FP_SIZE + (ETH_FP_SIZE - 48);
+            out[pos..pos + 48].copy_from_slice(fp);
+        }
+        out
+    }
+
+    /// Decodes a signature from the Ethereum encoding, see `to_eth_bytes`.
+    ///
+    /// Fails if the padding isn't zero, or if the point is not in the G2 subgroup.
+    pub fn from_eth_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != ETH_SIG_SIZE {
+            bail!(
+                "Invalid Ethereum signature length {}, expected {}",
+                bytes.len(),
+                ETH_SIG_SIZE
+            )
+        }
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(Signature(G2Projective::identity()));
+        }
+        let mut raw = [0u8; 2 * SIGSIZE];
+        for (i, fp) in bytes.chunks(ETH_FP_SIZE).enumerate() {
+            let (padding, value) = fp.split_at(ETH_FP_SIZE - 48);
+            if padding.iter().any(|b| *b != 0) || value[0] & 0xe0 != 0 {
+                bail!("Invalid Ethereum signature encoding")
+            }
+            raw[(i ^ 1) * 48..(i ^ 1) * 48 + 48].copy_from_slice(value);
+        }
+        match Option::<G2Affine>::from(G2Affine::from_uncompressed(&raw)) {
+            Some(affine) => Ok(Signature(affine.into())),
+            None => bail!("Invalid Ethereum signature encoding"),
+        }
+    }
+}
+
+impl Hash for Signature {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_bytes().as_ref().hash(state);
+    }
+}
+
+impl PartialOrd for Signature {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Signature {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_g2_projective(&self.0, &other.0)
     }
 }
 
@@ -33,24 +256,18 @@ impl Serialize for Signature {
 
 struct SigVisitor;
 
-fn coerce_size(v: &[u8]) -> &[u8; SIGSIZE] {
-    v.try_into().expect("Signature with incorrect length")
-}
-
 impl<'de> Visitor<'de> for SigVisitor {
     type Value = Signature;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("an integer between -2^31 and 2^31")
+        write!(formatter, "a compressed G2 point of {} bytes", SIGSIZE)
     }
 
     fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(Signature(G2Projective::from(
-            G2Affine::from_compressed(coerce_size(v)).unwrap(),
-        )))
+        Signature::from_bytes(v).map_err(E::custom)
     }
 }
 
@@ -63,24 +280,62 @@ impl<'de> Deserialize<'de> for Signature {
     }
 }
 
-pub fn aggregate(sigs: &[Signature]) -> Result<Signature> {
-    let agg = &sigs[0];
-
-    if !agg.is_valid() {
-        bail!("Cannot validate signature {:?}", agg)
+/// Returns the sum of the signatures, e.g. of different messages for `verify_messages`.
+///
+/// Fails with `Error::EmptyAggregate` if there are none, or with `Error::InvalidSignature` and
+/// the position of the first signature that isn't valid, see `Signature::is_valid`.
+pub fn aggregate<I, B>(sigs: I) -> std::result::Result<Signature, Error>
+where
+    I: IntoIterator<Item = B>,
+    B: Borrow<Signature>,
+{
+    let mut sigs = sigs.into_iter().enumerate();
+    let mut aggregate = match sigs.next() {
+        Some((_, sig)) if sig.borrow().is_valid() => *sig.borrow(),
+        Some(_) => return Err(Error::InvalidSignature(0)),
+        None => return Err(Error::EmptyAggregate),
+    };
+    for (i, sig) in sigs {
+        if !sig.borrow().is_valid() {
+            return Err(Error::InvalidSignature(i));
+        }
+        aggregate.0.add_assign(&sig.borrow().0);
     }
+    Ok(aggregate)
+}
 
-    let mut aggregate = G2Projective::from(sigs[0].0);
-
-    for i in 1..sigs.len() {
-        let next = &sigs[i];
-        if !next.is_valid() {
-            bail!("Cannot validate signature {:?}", next)
+/// Removes signatures from an aggregate, e.g. those of a signer that turned out to be faulty,
+/// without re-aggregating the rest.
+///
+/// Like `aggregate`, this fails with `Error::InvalidSignature` and the position of the first
+/// signature in `to_remove` that isn't valid. It also fails with `Error::EmptyAggregate` if
+/// nothing would be left, i.e. the result is the identity.
+pub fn deaggregate(
+    agg: &Signature,
+    to_remove: &[Signature],
+) -> std::result::Result<Signature, Error> {
+    let mut result = *agg;
+    for (i, sig) in to_remove.iter().enumerate() {
+        if !sig.is_valid() {
+            return Err(Error::InvalidSignature(i));
         }
-        aggregate.add_assign(&next.0)
+        result = result.remove(sig);
+    }
+    if bool::from(result.0.is_identity()) {
+        return Err(Error::EmptyAggregate);
     }
+    Ok(result)
+}
 
-    Ok(Signature(aggregate))
+/// Adds `sig` to the aggregate `agg`, e.g. as signatures arrive one at a time.
+///
+/// Fails with `Error::InvalidSignature(0)`, leaving `agg` unchanged, if `sig` isn't valid.
+pub fn aggregate_in_place(agg: &mut Signature, sig: &Signature) -> std::result::Result<(), Error> {
+    if !sig.is_valid() {
+        return Err(Error::InvalidSignature(0));
+    }
+    agg.0 += sig.0;
+    Ok(())
 }
 
 pub fn core_aggregate_verify(
@@ -103,46 +358,254 @@ pub fn core_aggregate_verify(
         bail!("Length mismatch for public_keys and hashes!")
     }
 
-    // Bail if non-unique hashes found!
-    for i in 0..(num_hashes - 1) {
-        for j in (i + 1)..num_hashes {
-            let a = hashes[i];
-            let b = hashes[j];
-            if a == b {
-                bail!("Non-unique hashes found! {:?} {:?}", a, b)
+    PreparedHashes::new(hashes)?.verify(signature, public_keys)
+}
+
+/// Verifies an aggregate signature like `core_aggregate_verify`, but allows the same hash to
+/// appear more than once.
+///
+/// The public keys of identical hashes are summed, and the signature is checked against the
+/// reduced product `\prod_h e(\sum_{hash_i = h} pk_i, h)`. Like `fast_aggregate_verify`, this is
+/// only safe if every public key comes with a verified proof of possession: without it, a signer
+/// can cancel out the keys of others who signed the same message.
+pub fn core_aggregate_verify_dedup(
+    signature: &Signature,
+    hashes: &[G2Projective],
+    public_keys: &[PublicKey],
+) -> Result<bool> {
+    if hashes.is_empty() || public_keys.is_empty() {
+        bail!(
+            "Either hashes {:?} or public_keys {:?} is empty",
+            hashes,
+            public_keys
+        )
+    }
+    if hashes.len() != public_keys.len() {
+        bail!("Length mismatch for public_keys and hashes!")
+    }
+
+    let mut affine = vec![G2Affine::identity(); hashes.len()];
+    G2Projective::batch_normalize(hashes, &mut affine);
+    let mut positions: HashMap<[u8; SIGSIZE], usize> = HashMap::with_capacity(hashes.len());
+    let mut unique_hashes: Vec<G2Projective> = Vec::with_capacity(hashes.len());
+    let mut summed_pks: Vec<PublicKey> = Vec::with_capacity(hashes.len());
+    for ((h, hash), pk) in affine.iter().zip(hashes).zip(public_keys) {
+        match positions.get(&h.to_compressed()) {
+            Some(&pos) => summed_pks[pos].0 += pk.0,
+            None => {
+                positions.insert(h.to_compressed(), unique_hashes.len());
+                unique_hashes.push(*hash);
+                summed_pks.push(*pk);
+            }
+        }
+    }
+
+    PreparedHashes::new(&unique_hashes)?.verify(signature, &summed_pks)
+}
+
+/// Bails if `hashes` contains the same hash more than once.
+///
+/// The hashes are normalized in one batch and compared by their compressed encodings, so this
+/// takes linear time.
+fn check_unique_hashes(hashes: &[G2Projective]) -> Result<()> {
+    let mut affine = vec![G2Affine::identity(); hashes.len()];
+    G2Projective::batch_normalize(hashes, &mut affine);
+    let mut seen = HashMap::with_capacity(hashes.len());
+    for (j, h) in affine.iter().enumerate() {
+        if let Some(i) = seen.insert(h.to_compressed(), j) {
+            bail!("Non-unique hashes found! {:?} {:?}", hashes[i], hashes[j])
+        }
+    }
+    Ok(())
+}
+
+/// Message hashes prepared for the Miller loop.
+///
+/// Preparing a hash is a significant part of the cost of aggregate verification. When the same
+/// messages are verified repeatedly, e.g. when re-verifying a block's signature, the hashes can be
+/// prepared once and reused.
+#[derive(Clone, Debug)]
+pub struct PreparedHashes(Vec<G2Prepared>);
+
+impl PreparedHashes {
+    /// Prepares the given hashes. Bails if `hashes` is empty or contains duplicates.
+    pub fn new(hashes: &[G2Projective]) -> Result<Self> {
+        if hashes.is_empty() {
+            bail!("Cannot prepare an empty list of hashes")
+        }
+        check_unique_hashes(hashes)?;
+        let prepared = hashes
+            .iter()
+            .map(|h| G2Prepared::from(G2Affine::from(h)))
+            .collect();
+        Ok(PreparedHashes(prepared))
+    }
+
+    /// Hashes and prepares the given messages. Bails if `messages` is empty or contains
+    /// duplicates.
+    ///
+    /// Each hash is checked and prepared as soon as it is computed, without collecting the hashes
+    /// first.
+    pub fn from_messages<M: AsRef<[u8]>>(messages: &[M]) -> Result<Self> {
+        PreparedHashes::hash_and_prepare(messages, |msg| hash_g2(msg))
+    }
+
+    /// Hashes and prepares the given messages under the domain separation tag `dst`, see
+    /// `hash_g2_with_dst`. Bails if `messages` is empty or contains duplicates.
+    pub fn from_messages_with_dst<M: AsRef<[u8]>>(dst: &[u8], messages: &[M]) -> Result<Self> {
+        PreparedHashes::hash_and_prepare(messages, |msg| hash_g2_with_dst(dst, msg))
+    }
+
+    fn hash_and_prepare<I, H>(messages: I, hash: H) -> Result<Self>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+        H: Fn(&[u8]) -> G2Projective,
+    {
+        let messages = messages.into_iter();
+        let mut seen = HashSet::with_capacity(messages.size_hint().0);
+        let mut prepared = Vec::with_capacity(messages.size_hint().0);
+        for msg in messages {
+            let hash = hash(msg.as_ref()).to_affine();
+            if !seen.insert(hash.to_compressed()) {
+                bail!("Non-unique hashes found! {:?}", hash)
             }
+            prepared.push(G2Prepared::from(hash));
         }
+        if prepared.is_empty() {
+            bail!("Cannot prepare an empty list of hashes")
+        }
+        Ok(PreparedHashes(prepared))
+    }
+
+    /// Returns the number of prepared hashes.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no prepared hashes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 
-    let c1: Gt = public_keys
-        .iter()
-        .zip(hashes.iter())
-        .map(|(pk, h)| {
-            let pk = G1Affine::from(pk.0);
-            let h = G2Prepared::from(G2Affine::from(*h));
-            multi_miller_loop(&[(&pk, &h)])
-        })
-        .fold(MillerLoopResult::default(), |mut acc, cur| {
-            acc = acc.mul(&cur);
-            acc
-        })
-        .final_exponentiation();
+    /// Verifies that `signature` is the aggregated signature of the prepared hashes under the
+    /// corresponding `public_keys`.
+    pub fn verify(&self, signature: &Signature, public_keys: &[PublicKey]) -> Result<bool> {
+        if self.0.len() != public_keys.len() {
+            bail!("Length mismatch for public_keys and hashes!")
+        }
 
-    let c2: Gt = pairing(&G1Affine::generator(), &G2Affine::from(signature.0));
+        // `e(g, sig) == \prod_i e(pk_i, hash_i)`, as a single multi-Miller loop over all pairs and
+        // one final exponentiation.
+        let neg_g = -G1Affine::generator();
+        let sig = G2Prepared::from(G2Affine::from(signature.0));
+        let pks: Vec<G1Affine> = public_keys.iter().map(|pk| G1Affine::from(pk.0)).collect();
+        let mut terms: Vec<(&G1Affine, &G2Prepared)> = Vec::with_capacity(pks.len() + 1);
+        terms.push((&neg_g, &sig));
+        terms.extend(pks.iter().zip(self.0.iter()));
+        let gt = multi_miller_loop(&terms).final_exponentiation();
+        Ok(bool::from(gt.is_identity()))
+    }
+}
 
-    Ok(c1 == c2)
+/// Verifies a signature aggregated from signatures of `msg` by each of `public_keys`, unlike
+/// `verify_messages`, which requires distinct messages.
+///
+/// The public keys are summed, and the signature is checked against the sum with a single
+/// multi-Miller loop. Fails if `public_keys` is empty or contains the identity.
+///
+/// This is only safe if every public key comes with a verified proof of possession of its secret
+/// key: otherwise a signer can pick their key as a function of the others' and forge an aggregate
+/// signature that all of them appear to have signed.
+pub fn fast_aggregate_verify(
+    signature: &Signature,
+    msg: &[u8],
+    public_keys: &[PublicKey],
+) -> Result<bool> {
+    Ok(fast_aggregate_verify_with_key(signature, msg, public_keys)?.is_some())
+}
+
+/// Verifies the signature like `fast_aggregate_verify`, and returns the aggregated public key if
+/// it is valid, e.g. to cache it for later signatures by the same signers.
+pub fn fast_aggregate_verify_with_key(
+    signature: &Signature,
+    msg: &[u8],
+    public_keys: &[PublicKey],
+) -> Result<Option<PublicKey>> {
+    let pk = aggregate_public_keys(public_keys)?;
+    Ok(Some(pk).filter(|pk| pk.verify(signature, msg)))
 }
 
 /// Verifies that the signature is the actual aggregated signature of messages - pubkeys.
 /// Calculated by `e(g1, signature) == \prod_{i = 0}^n e(pk_i, hash_i)`.
-pub fn verify_messages(
+///
+/// The messages can be any byte containers, e.g. `&[&[u8]]` or `Vec<Vec<u8>>`.
+pub fn verify_messages<M: AsRef<[u8]>>(
     signature: &Signature,
-    messages: &[&[u8]],
+    messages: &[M],
     public_keys: &[PublicKey],
 ) -> Result<bool> {
-    let hashes: Vec<_> = messages.iter().map(|msg| hash_g2(msg)).collect();
+    if messages.is_empty() || public_keys.is_empty() {
+        bail!(
+            "Either messages ({}) or public_keys {:?} is empty",
+            messages.len(),
+            public_keys
+        )
+    }
+    if messages.len() != public_keys.len() {
+        bail!("Length mismatch for public_keys and messages!")
+    }
+
+    PreparedHashes::from_messages(messages)?.verify(signature, public_keys)
+}
+
+/// Verifies a signature aggregated from signatures of the same message by each of `public_keys`.
+///
+/// This is a single pairing check against the aggregated public key, see `aggregate_public_keys`.
+/// It is the same check as `fast_aggregate_verify`, and only safe under the same conditions.
+#[deprecated(note = "use `fast_aggregate_verify`, which is the same check")]
+pub fn verify_aggregate_same_message<M: AsRef<[u8]>>(
+    signature: &Signature,
+    msg: M,
+    public_keys: &[PublicKey],
+) -> Result<bool> {
+    fast_aggregate_verify(signature, msg.as_ref(), public_keys)
+}
 
-    core_aggregate_verify(signature, &hashes, public_keys)
+/// Verifies an aggregate like `verify_messages`, but takes the `(message, public key)` pairs from
+/// an iterator, e.g. over a map, instead of two slices.
+///
+/// Each message is hashed and prepared as it is read. Fails if there are no pairs, or if two
+/// messages are equal.
+pub fn verify_messages_iter<'a, I>(signature: &Signature, items: I) -> Result<bool>
+where
+    I: IntoIterator<Item = (&'a [u8], &'a PublicKey)>,
+{
+    let mut public_keys = Vec::new();
+    let messages = items.into_iter().map(|(msg, pk)| {
+        public_keys.push(*pk);
+        msg
+    });
+    let hashes = PreparedHashes::hash_and_prepare(messages, |msg| hash_g2(msg))?;
+    hashes.verify(signature, &public_keys)
+}
+
+/// Verifies an aggregate like `verify_messages`, for signatures made with
+/// `SecretKey::sign_with_dst` under the domain separation tag `dst`.
+pub fn verify_messages_with_dst<M: AsRef<[u8]>>(
+    signature: &Signature,
+    dst: &[u8],
+    messages: &[M],
+    public_keys: &[PublicKey],
+) -> Result<bool> {
+    if messages.is_empty() || messages.len() != public_keys.len() {
+        bail!(
+            "Cannot verify {} messages against {} public keys",
+            messages.len(),
+            public_keys.len()
+        )
+    }
+    PreparedHashes::from_messages_with_dst(dst, messages)?.verify(signature, public_keys)
 }
 
 #[cfg(test)]
@@ -150,7 +613,181 @@ mod tests {
     use core::panic;
 
     use super::*;
+    use crate::pk::POP_DST;
     use crate::sk::SecretKey;
+    use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn unique_hashes() {
+        // Distinct points, without hashing 1000 messages.
+        let mut hashes = Vec::with_capacity(1001);
+        let mut h = hash_g2(b"Rip and tear, until it's done");
+        for _ in 0..1000 {
+            hashes.push(h);
+            h += G2Projective::generator();
+        }
+        assert!(check_unique_hashes(&hashes).is_ok());
+
+        // The same point in other projective coordinates is still a duplicate.
+        hashes.push(G2Projective::from(hashes[500].to_affine()));
+        let err = check_unique_hashes(&hashes).unwrap_err().to_string();
+        assert!(err.starts_with("Non-unique hashes found!"));
+        assert!(PreparedHashes::new(&hashes).is_err());
+
+        assert!(check_unique_hashes(&[]).is_ok());
+        assert!(check_unique_hashes(&hashes[..1]).is_ok());
+    }
+
+    #[test]
+    fn hash_ord() {
+        let sk = SecretKey::random();
+        let sig0 = sk.sign(b"Rip and tear, until it's done");
+        let sig1 = sk.sign(b"Other msg");
+        // Equal signatures in different projective coordinates are deduplicated.
+        let sig0_affine = Signature(G2Projective::from(sig0.0.to_affine()));
+        let mut set = HashSet::new();
+        assert!(set.insert(sig0));
+        assert!(set.insert(sig1));
+        assert!(!set.insert(sig0_affine));
+        assert!(!set.insert(sig1));
+        assert_eq!(2, set.len());
+
+        assert_eq!(Ordering::Equal, sig0.cmp(&sig0_affine));
+        assert_eq!(sig0.to_bytes().cmp(&sig1.to_bytes()), sig0.cmp(&sig1));
+        let tree: BTreeSet<_> = vec![sig1, sig0, sig0_affine, sig1].into_iter().collect();
+        assert_eq!(2, tree.len());
+    }
+
+    #[test]
+    fn random_output() {
+        // Known answers, checked against SHAKE256 and the group order in Python.
+        let generator = Signature(G2Projective::generator());
+        assert_eq!(
+            from_hex("f52d6e64492d50afe7f40df14ddcec4d"),
+            generator.to_random_bytes::<16>().to_vec()
+        );
+        assert_eq!(
+            from_hex(concat!(
+                "f52d6e64492d50afe7f40df14ddcec4dbdb86deda67f463e3ef4814cd0b9cb50",
+                "50444c283b475f38ea4db197a7800bdb"
+            )),
+            generator.to_random_bytes::<48>().to_vec()
+        );
+        assert_eq!(
+            from_hex("d528bd4c04ad79a74ee4d0e6533ed6973401c367d9ff4b512ebfc705c26cfb6e"),
+            generator.to_scalar().to_bytes().to_vec()
+        );
+
+        // The outputs survive a serialization round trip, and differ between signatures.
+        let sig = SecretKey::random().sign(b"Rip and tear, until it's done");
+        let deser: Signature = bincode::deserialize(&bincode::serialize(&sig).unwrap()).unwrap();
+        assert_eq!(sig.to_random_bytes::<32>(), deser.to_random_bytes::<32>());
+        assert_eq!(sig.to_scalar(), deser.to_scalar());
+        assert_ne!(
+            generator.to_random_bytes::<32>(),
+            sig.to_random_bytes::<32>()
+        );
+        assert_ne!(generator.to_scalar(), sig.to_scalar());
+    }
+
+    #[test]
+    fn normalize() {
+        let sk = SecretKey::random();
+        let pk = sk.public_key();
+        let msg = b"Rip and tear, until it's done";
+        let sig = sk.sign(msg);
+
+        // A sum of shares has an arbitrary projective representation.
+        let mut sum = Signature(sig.0 * Scalar::from(3) - sig.0 - sig.0);
+        assert_eq!(sig, sum);
+        sum.normalize();
+        assert_eq!(sig, sum);
+        assert_eq!(sig.to_bytes(), sum.to_bytes());
+        assert!(pk.verify(&sum, msg));
+        let once = sum;
+        sum.normalize();
+        assert_eq!(format!("{:?}", once), format!("{:?}", sum));
+
+        // The negation of a signature is not a signature of the same message.
+        let neg = Signature(-sig.0);
+        assert_ne!(sig.to_bytes(), neg.to_bytes());
+        assert!(!pk.verify(&neg, msg));
+
+        let mut identity = Signature(G2Projective::identity());
+        identity.normalize();
+        assert_eq!(Signature(G2Projective::identity()), identity);
+    }
+
+    #[test]
+    fn coin_values() {
+        // Known answers, checked against SHA3-256 of the compressed encodings in Python.
+        let generator = Signature(G2Projective::generator());
+        assert_eq!(0xa9f0431a6aeda844, generator.to_u64());
+        assert!(!generator.parity());
+        let identity = Signature(G2Projective::identity());
+        assert_eq!(0xee731f0b1e4bed3a, identity.to_u64());
+        assert!(!identity.parity());
+        let sig = SecretKey::from_raw([1, 2, 3, 4]).sign(b"Rip and tear, until it's done");
+        // The signed message is hashed differently with the `hash-to-curve` feature.
+        #[cfg(not(feature = "hash-to-curve"))]
+        {
+            assert_eq!(0xf8d40cc9ed6ca4e9, sig.to_u64());
+            assert!(sig.parity());
+        }
+
+        // The values survive a serialization round trip.
+        let bytes = bincode::serialize(&sig).unwrap();
+        let deser: Signature = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(sig.to_u64(), deser.to_u64());
+        assert_eq!(sig.parity(), deser.parity());
+    }
+
+    #[test]
+    fn eth_bytes() {
+        // The G2 generator, as listed in EIP-2537.
+        let generator = from_hex(concat!(
+            "00000000000000000000000000000000",
+            "024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8",
+            "00000000000000000000000000000000",
+            "13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e",
+            "00000000000000000000000000000000",
+            "0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801",
+            "00000000000000000000000000000000",
+            "0606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be",
+        ));
+        let sig = Signature(G2Projective::generator());
+        assert_eq!(&generator[..], &sig.to_eth_bytes()[..]);
+        assert_eq!(sig, Signature::from_eth_bytes(&generator).unwrap());
+
+        let identity = Signature(G2Projective::identity());
+        assert_eq!([0u8; ETH_SIG_SIZE][..], identity.to_eth_bytes()[..]);
+        assert_eq!(
+            identity,
+            Signature::from_eth_bytes(&[0u8; ETH_SIG_SIZE]).unwrap()
+        );
+
+        let sig = SecretKey::random().sign(b"Rip and tear, until it's done");
+        assert_eq!(sig, Signature::from_eth_bytes(&sig.to_eth_bytes()).unwrap());
+
+        let mut bad = generator.clone();
+        bad[0] = 1;
+        assert!(Signature::from_eth_bytes(&bad).is_err());
+        let mut bad = generator.clone();
+        bad[16] |= 0x80;
+        assert!(Signature::from_eth_bytes(&bad).is_err());
+        let mut bad = generator.clone();
+        bad[ETH_SIG_SIZE - 1] ^= 1;
+        assert!(Signature::from_eth_bytes(&bad).is_err());
+        assert!(Signature::from_eth_bytes(&generator[1..]).is_err());
+        assert!(Signature::from_eth_bytes(&sig.0.to_affine().to_compressed()).is_err());
+    }
 
     #[test]
     fn verify_agg() {
@@ -165,7 +802,7 @@ mod tests {
         let sig1 = sk1.sign(msg1);
         let sig2 = sk2.sign(msg2);
 
-        if let Ok(agg_sig) = aggregate(&[sig1, sig2]) {
+        if let Ok(agg_sig) = aggregate([sig1, sig2]) {
             if let Ok(res) = verify_messages(&agg_sig, &[msg1, msg2], &[pk1, pk2]) {
                 assert!(res)
             } else {
@@ -176,12 +813,338 @@ mod tests {
         }
     }
 
+    #[test]
+    fn aggregate_verify_dedup() {
+        let sks: Vec<_> = (0..3).map(|_| SecretKey::random()).collect();
+        let pks: Vec<_> = sks.iter().map(SecretKey::public_key).collect();
+        let msgs: [&[u8]; 3] = [b"yes", b"yes", b"no"];
+        let sigs: Vec<_> = sks
+            .iter()
+            .zip(&msgs)
+            .map(|(sk, msg)| sk.sign(msg))
+            .collect();
+        let agg_sig = aggregate(&sigs).unwrap();
+        let hashes: Vec<_> = msgs.iter().map(hash_g2).collect();
+
+        assert!(core_aggregate_verify_dedup(&agg_sig, &hashes, &pks).unwrap());
+        assert!(core_aggregate_verify(&agg_sig, &hashes, &pks).is_err());
+        assert!(verify_messages(&agg_sig, &msgs, &pks).is_err());
+
+        // Distinct hashes give the same result as the strict check.
+        let distinct = [hashes[0], hashes[2]];
+        let sig = aggregate(&sigs[1..]).unwrap();
+        assert!(core_aggregate_verify(&sig, &distinct, &pks[1..]).unwrap());
+        assert!(core_aggregate_verify_dedup(&sig, &distinct, &pks[1..]).unwrap());
+
+        // Swapping the messages of two signers fails.
+        let swapped = [hashes[0], hashes[2], hashes[1]];
+        assert!(!core_aggregate_verify_dedup(&agg_sig, &swapped, &pks).unwrap());
+        assert!(!core_aggregate_verify_dedup(&agg_sig, &hashes[1..], &pks[1..]).unwrap());
+        assert!(core_aggregate_verify_dedup(&agg_sig, &hashes, &pks[1..]).is_err());
+        assert!(core_aggregate_verify_dedup(&agg_sig, &[], &[]).is_err());
+    }
+
+    #[test]
+    fn fast_aggregate() {
+        let sks: Vec<_> = (0..10).map(|_| SecretKey::random()).collect();
+        let pks: Vec<_> = sks.iter().map(SecretKey::public_key).collect();
+        let msg = b"Rip and tear, until it's done";
+        let sigs: Vec<_> = sks.iter().map(|sk| sk.sign(msg)).collect();
+
+        let agg_sig = aggregate(&sigs).unwrap();
+        assert!(fast_aggregate_verify(&agg_sig, msg, &pks).unwrap());
+        // `verify_messages` rejects the repeated message.
+        assert!(verify_messages(&agg_sig, &[&msg[..]; 10], &pks).is_err());
+
+        // Dropping one signer's key, or their signature, fails.
+        assert!(!fast_aggregate_verify(&agg_sig, msg, &pks[1..]).unwrap());
+        let partial_sig = aggregate(&sigs[1..]).unwrap();
+        assert!(!fast_aggregate_verify(&partial_sig, msg, &pks).unwrap());
+        assert!(fast_aggregate_verify(&partial_sig, msg, &pks[1..]).unwrap());
+
+        assert!(!fast_aggregate_verify(&agg_sig, b"Other msg", &pks).unwrap());
+        assert!(fast_aggregate_verify(&agg_sig, msg, &[]).is_err());
+    }
+
+    #[test]
+    fn fast_aggregate_with_key() {
+        let sks: Vec<_> = (0..5).map(|_| SecretKey::random()).collect();
+        let pks: Vec<_> = sks.iter().map(SecretKey::public_key).collect();
+        let pops: Vec<_> = sks.iter().map(SecretKey::prove_possession).collect();
+        let msg = b"Rip and tear, until it's done";
+        let agg_sig = aggregate(sks.iter().map(|sk| sk.sign(msg))).unwrap();
+
+        let manual = PublicKey(pks.iter().map(|pk| pk.0).sum());
+        let agg_pk = fast_aggregate_verify_with_key(&agg_sig, msg, &pks)
+            .unwrap()
+            .expect("valid aggregate signature");
+        assert_eq!(manual, agg_pk);
+        assert!(agg_pk.verify(&agg_sig, msg));
+        assert_eq!(agg_pk, PublicKey::aggregate_checked(&pks, &pops).unwrap());
+        assert!(fast_aggregate_verify_with_key(&agg_sig, b"Other msg", &pks)
+            .unwrap()
+            .is_none());
+
+        // A rogue key chosen to cancel out the others can't come with a proof of possession.
+        let rogue_sk = SecretKey::random();
+        let rogue_pk = PublicKey(rogue_sk.public_key().0 - manual.0);
+        let mut with_rogue = pks.clone();
+        with_rogue.push(rogue_pk);
+        let forged = rogue_sk.sign(msg);
+        assert!(fast_aggregate_verify(&forged, msg, &with_rogue).unwrap());
+        let mut rogue_pops = pops.clone();
+        rogue_pops.push(rogue_sk.prove_possession());
+        let err = PublicKey::aggregate_checked(&with_rogue, &rogue_pops).unwrap_err();
+        assert_eq!(
+            "Invalid proof of possession for public key 5",
+            err.to_string()
+        );
+
+        // A proof is not a signature of the public key's bytes, and vice versa.
+        assert!(!pks[0].verify(&pops[0], pks[0].to_bytes()));
+        assert!(!pks[0].verify_possession(&sks[0].sign(pks[0].to_bytes())));
+        // Nor is it a plain signature of the public key framed with the tag.
+        let mut framed = (POP_DST.len() as u64).to_be_bytes().to_vec();
+        framed.extend_from_slice(POP_DST);
+        framed.extend_from_slice(&pks[0].to_bytes());
+        assert!(!pks[0].verify_possession(&sks[0].sign(&framed)));
+        assert!(!pks[0].verify_possession(&sks[0].sign(&framed[8..])));
+        assert!(pks[0].verify_with_dst(&pops[0], POP_DST, pks[0].to_bytes()));
+        assert!(PublicKey::aggregate_checked(&pks, &pops[1..]).is_err());
+        assert!(PublicKey::aggregate_checked(&[], &[]).is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn verify_agg_same_message() {
+        let sks: Vec<_> = (0..3).map(|_| SecretKey::random()).collect();
+        let pks: Vec<_> = sks.iter().map(SecretKey::public_key).collect();
+        let msg = b"Rip and tear";
+
+        let sigs: Vec<_> = sks.iter().map(|sk| sk.sign(msg)).collect();
+        let agg_sig = aggregate(&sigs).unwrap();
+        assert!(verify_aggregate_same_message(&agg_sig, msg, &pks).unwrap());
+        assert!(!verify_aggregate_same_message(&agg_sig, b"till is done", &pks).unwrap());
+        assert!(!verify_aggregate_same_message(&agg_sig, msg, &pks[..2]).unwrap());
+
+        let agg_pk = aggregate_public_keys(&pks).unwrap();
+        assert_eq!(pks[0] + pks[1] + pks[2], agg_pk);
+        assert!(agg_pk.verify(&agg_sig, msg));
+
+        // One signer signed a different message.
+        let mut sigs = sigs;
+        sigs[1] = sks[1].sign(b"till is done");
+        let agg_sig = aggregate(&sigs).unwrap();
+        assert!(!verify_aggregate_same_message(&agg_sig, msg, &pks).unwrap());
+
+        assert!(verify_aggregate_same_message(&agg_sig, msg, &[]).is_err());
+        let identity = PublicKey(bls12_381::G1Projective::identity());
+        assert!(aggregate_public_keys(&[pks[0], identity]).is_err());
+    }
+
+    #[test]
+    fn verify_prepared() {
+        let sk1 = SecretKey::random();
+        let sk2 = SecretKey::random();
+        let pks = [sk1.public_key(), sk2.public_key()];
+
+        let msg1 = b"Rip and tear";
+        let msg2 = b"till is done";
+        let msgs: [&[u8]; 2] = [msg1, msg2];
+
+        let agg_sig = aggregate([sk1.sign(msg1), sk2.sign(msg2)]).unwrap();
+        let prepared = PreparedHashes::from_messages(&msgs).unwrap();
+        assert_eq!(2, prepared.len());
+
+        // The prepared hashes can be reused for any number of verifications.
+        for _ in 0..3 {
+            assert!(prepared.verify(&agg_sig, &pks).unwrap());
+        }
+        assert!(verify_messages(&agg_sig, &msgs, &pks).unwrap());
+
+        // Owned messages work the same.
+        let owned: Vec<Vec<u8>> = msgs.iter().map(|msg| msg.to_vec()).collect();
+        let prepared_owned = PreparedHashes::from_messages(&owned).unwrap();
+        assert!(prepared_owned.verify(&agg_sig, &pks).unwrap());
+        assert!(verify_messages(&agg_sig, &owned, &pks).unwrap());
+        assert!(!verify_messages(&agg_sig, &owned[..1], &pks[..1]).unwrap());
+        assert!(verify_messages(&agg_sig, &owned[..1], &pks).is_err());
+
+        let other_sig = aggregate([sk1.sign(msg1), sk2.sign(b"Nooooooo")]).unwrap();
+        assert!(!prepared.verify(&other_sig, &pks).unwrap());
+        assert!(prepared.verify(&agg_sig, &pks[..1]).is_err());
+    }
+
+    #[test]
+    fn prepare_duplicate_hashes() {
+        let msg: &[u8] = b"Rip and tear";
+        assert!(PreparedHashes::from_messages(&[msg, msg]).is_err());
+        assert!(PreparedHashes::from_messages::<&[u8]>(&[]).is_err());
+        assert!(PreparedHashes::from_messages(&[msg.to_vec(), msg.to_vec()]).is_err());
+        assert!(
+            verify_messages::<Vec<u8>>(&Signature(G2Projective::identity()), &[], &[]).is_err()
+        );
+    }
+
     #[test]
     fn valid() {
         let sk = SecretKey::random();
         let msg = b"Rip and tear, until it's done";
         let sig = sk.sign(msg);
-        assert!(sig.is_valid())
+        assert!(sig.is_valid());
+        assert!(!Signature(G2Projective::identity()).is_valid());
+    }
+
+    #[test]
+    fn verify_messages_from_iter() {
+        let sks: Vec<_> = (0..5).map(|_| SecretKey::random()).collect();
+        let by_msg: BTreeMap<Vec<u8>, PublicKey> = sks
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| (format!("message {}", i).into_bytes(), sk.public_key()))
+            .collect();
+        let sig = aggregate(
+            sks.iter()
+                .enumerate()
+                .map(|(i, sk)| sk.sign(format!("message {}", i))),
+        )
+        .unwrap();
+        let msgs: Vec<&[u8]> = by_msg.keys().map(Vec::as_slice).collect();
+        let pks: Vec<PublicKey> = by_msg.values().copied().collect();
+
+        let items = || by_msg.iter().map(|(msg, pk)| (msg.as_slice(), pk));
+        assert!(verify_messages_iter(&sig, items()).unwrap());
+        assert_eq!(
+            verify_messages(&sig, &msgs, &pks).unwrap(),
+            verify_messages_iter(&sig, items()).unwrap()
+        );
+
+        // Dropping or misattributing a message fails, in both versions.
+        assert!(!verify_messages_iter(&sig, items().skip(1)).unwrap());
+        assert!(!verify_messages(&sig, &msgs[1..], &pks[1..]).unwrap());
+        let swapped = msgs.iter().copied().zip(pks.iter().rev());
+        assert!(!verify_messages_iter(&sig, swapped).unwrap());
+
+        let msg = &b"Rip and tear"[..];
+        assert!(verify_messages_iter(&sig, vec![(msg, &pks[0]), (msg, &pks[1])]).is_err());
+        assert!(verify_messages_iter(&sig, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn deaggregate_signer() {
+        let sks: Vec<_> = (0..5).map(|_| SecretKey::random()).collect();
+        let pks: Vec<_> = sks.iter().map(SecretKey::public_key).collect();
+        let msgs: Vec<Vec<u8>> = (1..=5)
+            .map(|i| format!("message {}", i).into_bytes())
+            .collect();
+        let sigs: Vec<_> = sks
+            .iter()
+            .zip(&msgs)
+            .map(|(sk, msg)| sk.sign(msg))
+            .collect();
+        let agg = aggregate(&sigs).unwrap();
+        assert!(verify_messages(&agg, &msgs, &pks).unwrap());
+
+        // Remove the signature of message 3.
+        let rest: Vec<usize> = vec![0, 1, 3, 4];
+        let rest_msgs: Vec<_> = rest.iter().map(|i| msgs[*i].clone()).collect();
+        let rest_pks: Vec<_> = rest.iter().map(|i| pks[*i]).collect();
+        let removed = agg.remove(&sigs[2]);
+        assert!(verify_messages(&removed, &rest_msgs, &rest_pks).unwrap());
+        assert!(!verify_messages(&agg, &rest_msgs, &rest_pks).unwrap());
+        let checked = deaggregate(&agg, &sigs[2..3]).unwrap();
+        assert_eq!(removed, checked);
+        assert_eq!(aggregate(rest.iter().map(|i| sigs[*i])).unwrap(), checked);
+
+        let both = deaggregate(&agg, &[sigs[0], sigs[4]]).unwrap();
+        assert!(verify_messages(&both, &msgs[1..4], &pks[1..4]).unwrap());
+        assert_eq!(agg, deaggregate(&agg, &[]).unwrap());
+
+        let identity = Signature(G2Projective::identity());
+        assert_eq!(
+            Err(Error::InvalidSignature(1)),
+            deaggregate(&agg, &[sigs[0], identity])
+        );
+        assert_eq!(Err(Error::EmptyAggregate), deaggregate(&agg, &sigs));
+    }
+
+    #[test]
+    fn domain_separation() {
+        let (dst_a, dst_b) = (&b"protocol-a"[..], &b"protocol-b"[..]);
+        let sks: Vec<_> = (0..2).map(|_| SecretKey::random()).collect();
+        let pks: Vec<_> = sks.iter().map(SecretKey::public_key).collect();
+        let msg = b"Rip and tear, until it's done";
+
+        let sig_a = sks[0].sign_with_dst(dst_a, msg);
+        let sig_b = sks[0].sign_with_dst(dst_b, msg);
+        assert_ne!(sig_a, sig_b);
+        assert!(pks[0].verify_with_dst(&sig_a, dst_a, msg));
+        assert!(!pks[0].verify_with_dst(&sig_a, dst_b, msg));
+        assert!(!pks[0].verify_with_dst(&sig_b, dst_a, msg));
+        assert!(!pks[0].verify(&sig_a, msg));
+        assert!(!pks[0].verify_with_dst(&sks[0].sign(msg), dst_a, msg));
+
+        // Moving bytes between the tag and the message changes the hash.
+        assert_ne!(hash_g2_with_dst(b"ab", b"c"), hash_g2_with_dst(b"a", b"bc"));
+
+        // Nor can a plain signature of the tag framed with the message pass for a tagged one.
+        let mut framed = (dst_a.len() as u64).to_be_bytes().to_vec();
+        framed.extend_from_slice(dst_a);
+        framed.extend_from_slice(msg);
+        assert!(!pks[0].verify_with_dst(&sks[0].sign(&framed), dst_a, msg));
+        framed.drain(..8);
+        assert!(!pks[0].verify_with_dst(&sks[0].sign(&framed), dst_a, msg));
+
+        let msgs: [&[u8]; 2] = [msg, b"Other msg"];
+        let agg = aggregate(
+            sks.iter()
+                .zip(&msgs)
+                .map(|(sk, m)| sk.sign_with_dst(dst_a, m)),
+        )
+        .unwrap();
+        assert!(verify_messages_with_dst(&agg, dst_a, &msgs, &pks).unwrap());
+        assert!(!verify_messages_with_dst(&agg, dst_b, &msgs, &pks).unwrap());
+        assert!(!verify_messages(&agg, &msgs, &pks).unwrap());
+        assert!(verify_messages_with_dst(&agg, dst_a, &msgs[..1], &pks).is_err());
+    }
+
+    #[test]
+    fn aggregate_inputs() {
+        let msg = b"Rip and tear, until it's done";
+        let sigs: Vec<_> = (0..4).map(|_| SecretKey::random().sign(msg)).collect();
+        let manual = Signature(sigs.iter().map(|sig| sig.0).sum());
+
+        // Any iterator of signatures or references works, in any order.
+        assert_eq!(manual, aggregate(&sigs).unwrap());
+        assert_eq!(manual, aggregate(sigs.iter().rev()).unwrap());
+        assert_eq!(manual, aggregate(sigs.clone()).unwrap());
+        let mut shuffled = sigs.clone();
+        shuffled.swap(0, 3);
+        shuffled.swap(1, 2);
+        assert_eq!(manual, aggregate(shuffled.into_iter()).unwrap());
+
+        let mut incremental = sigs[0];
+        for sig in &sigs[1..] {
+            aggregate_in_place(&mut incremental, sig).unwrap();
+        }
+        assert_eq!(manual, incremental);
+
+        // The identity is rejected, at whatever position it occurs.
+        let identity = Signature(G2Projective::identity());
+        for i in 0..=sigs.len() {
+            let mut with_identity = sigs.clone();
+            with_identity.insert(i, identity);
+            assert_eq!(Err(Error::InvalidSignature(i)), aggregate(&with_identity));
+        }
+        assert_eq!(
+            Err(Error::InvalidSignature(0)),
+            aggregate_in_place(&mut incremental, &identity)
+        );
+        assert_eq!(manual, incremental);
+        assert_eq!(
+            Err(Error::EmptyAggregate),
+            aggregate(Vec::<Signature>::new())
+        );
     }
 
     #[test]
@@ -200,9 +1163,9 @@ mod tests {
         let sig1 = sk1.sign(msg1);
         let sig2 = sk2.sign(msg2);
 
-        if let Ok(agg_sig) = aggregate(&[sig1, sig2]) {
+        if let Ok(agg_sig) = aggregate([sig1, sig2]) {
             // sig2 is over msg2 not msg3, expect test to fail
-            if let Ok(res) = verify_messages(&agg_sig, &[msg1, msg3], &[pk1, pk2]) {
+            if let Ok(res) = verify_messages(&agg_sig, &[&msg1[..], msg3], &[pk1, pk2]) {
                 assert!(res)
             } else {
                 assert!(false)
@@ -229,7 +1192,7 @@ mod tests {
         let sig3 = sk2.sign(msg3);
 
         // Signature is over msg3, but msg2 is being checked
-        if let Ok(agg_sig) = aggregate(&[sig1, sig3]) {
+        if let Ok(agg_sig) = aggregate([sig1, sig3]) {
             if let Ok(res) = verify_messages(&agg_sig, &[msg1, msg2], &[pk1, pk2]) {
                 assert!(res)
             } else {
@@ -256,7 +1219,7 @@ mod tests {
         let sig1 = sk1.sign(msg1);
         let sig2 = sk2.sign(msg2);
 
-        if let Ok(agg_sig) = aggregate(&[sig1, sig2]) {
+        if let Ok(agg_sig) = aggregate([sig1, sig2]) {
             // pk3 is not for msg2, expect test to fail
             if let Ok(res) = verify_messages(&agg_sig, &[msg1, msg2], &[pk1, pk3]) {
                 assert!(res)
@@ -282,7 +1245,7 @@ mod tests {
         let sig1 = sk1.sign(msg1);
         let sig2 = sk2.sign(msg2);
 
-        if let Ok(agg_sig) = aggregate(&[sig1, sig2]) {
+        if let Ok(agg_sig) = aggregate([sig1, sig2]) {
             // pk2 is missing, expect test to fail
             if let Ok(res) = verify_messages(&agg_sig, &[msg1, msg2], &[pk1]) {
                 assert!(res)
@@ -293,4 +1256,39 @@ mod tests {
             assert!(false)
         }
     }
+
+    #[test]
+    fn uncompressed_bytes() {
+        let sig = SecretKey::random().sign(b"Rip and tear, until it's done");
+        let compressed = sig.to_bytes();
+        let uncompressed = sig.to_bytes_uncompressed();
+        assert_eq!(192, uncompressed.len());
+        assert_eq!(sig, Signature::from_bytes(&compressed).unwrap());
+        assert_eq!(
+            sig,
+            Signature::from_bytes_uncompressed(&uncompressed).unwrap()
+        );
+        // Both encodings start with `x`, apart from the flag bits.
+        assert_eq!(compressed[0] & 0x1f, uncompressed[0]);
+        assert_eq!(compressed[1..], uncompressed[1..96]);
+
+        let identity = Signature(G2Projective::identity());
+        let bytes = identity.to_bytes_uncompressed();
+        assert_eq!(
+            identity,
+            Signature::from_bytes_uncompressed(&bytes).unwrap()
+        );
+
+        assert!(Signature::from_bytes_uncompressed(&compressed).is_err());
+        assert!(Signature::from_bytes(&uncompressed).is_err());
+        let mut off_curve = uncompressed;
+        off_curve[191] ^= 1;
+        assert!(Signature::from_bytes_uncompressed(&off_curve).is_err());
+        // A point on the curve with `x = 2`, outside the prime-order subgroup.
+        let mut bytes = [0u8; 96];
+        bytes[0] = 0x80;
+        bytes[95] = 2;
+        let point = G2Affine::from_compressed_unchecked(&bytes).unwrap();
+        assert!(Signature::from_bytes_uncompressed(&point.to_uncompressed()).is_err());
+    }
 }
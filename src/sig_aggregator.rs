@@ -0,0 +1,104 @@
+use crate::{aggregate_in_place, Error, Signature};
+use bls12_381::G2Projective;
+use group::Group;
+
+/// Aggregates signatures one at a time, as they arrive.
+///
+/// The result is the same as `aggregate` of all the added signatures, but each addition only
+/// costs one point addition, instead of re-aggregating everything received so far.
+#[derive(Clone, Debug)]
+pub struct SignatureAggregator {
+    sum: Signature,
+    count: usize,
+}
+
+impl Default for SignatureAggregator {
+    fn default() -> Self {
+        SignatureAggregator::new()
+    }
+}
+
+impl SignatureAggregator {
+    /// Creates an aggregator without any signatures.
+    pub fn new() -> Self {
+        SignatureAggregator {
+            sum: Signature(G2Projective::identity()),
+            count: 0,
+        }
+    }
+
+    /// Adds a signature to the aggregate.
+    ///
+    /// Fails with `Error::InvalidSignature(0)`, leaving the aggregate unchanged, if the signature
+    /// isn't valid, see `aggregate_in_place`.
+    pub fn add(&mut self, sig: &Signature) -> Result<(), Error> {
+        aggregate_in_place(&mut self.sum, sig)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Returns the number of signatures added so far.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if no signatures have been added.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the aggregate of the signatures added so far, or `Error::EmptyAggregate` if there
+    /// are none.
+    pub fn finalize(&self) -> Result<Signature, Error> {
+        if self.is_empty() {
+            return Err(Error::EmptyAggregate);
+        }
+        Ok(self.sum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{aggregate, verify_messages, SecretKey};
+
+    #[test]
+    fn incremental() {
+        let sks: Vec<_> = (0..10).map(|_| SecretKey::random()).collect();
+        let pks: Vec<_> = sks.iter().map(SecretKey::public_key).collect();
+        let msgs: Vec<Vec<u8>> = (0..10u32).map(|i| i.to_be_bytes().to_vec()).collect();
+        let msg_refs: Vec<&[u8]> = msgs.iter().map(Vec::as_slice).collect();
+        let sigs: Vec<_> = sks
+            .iter()
+            .zip(&msgs)
+            .map(|(sk, msg)| sk.sign(msg))
+            .collect();
+
+        let mut aggregator = SignatureAggregator::new();
+        assert!(aggregator.is_empty());
+        assert_eq!(Err(Error::EmptyAggregate), aggregator.finalize());
+        for (i, sig) in sigs.iter().enumerate() {
+            aggregator.add(sig).expect("valid signature");
+            assert_eq!(i + 1, aggregator.len());
+        }
+
+        let agg = aggregator.finalize().expect("failed to finalize");
+        assert_eq!(aggregate(&sigs).unwrap(), agg);
+        assert!(verify_messages(&agg, &msg_refs, &pks).unwrap());
+        assert!(!verify_messages(&agg, &msg_refs[1..], &pks[1..]).unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        let sig = SecretKey::random().sign(b"Rip and tear, until it's done");
+        let mut aggregator = SignatureAggregator::new();
+        aggregator.add(&sig).unwrap();
+
+        assert_eq!(
+            Err(Error::InvalidSignature(0)),
+            aggregator.add(&Signature(G2Projective::identity()))
+        );
+        assert_eq!(1, aggregator.len());
+        assert_eq!(sig, aggregator.finalize().unwrap());
+    }
+}
@@ -1,4 +1,96 @@
 use crate::sig::Signature;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+/// A signature share. It is hashed and ordered by the compressed encoding of the signature.
+#[derive(Clone, PartialEq, Eq, Debug, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct SignatureShare(pub Signature);
+
+impl SignatureShare {
+    /// Returns the share as a signature.
+    ///
+    /// This is only meaningful if the share is by the master key itself, e.g. if the threshold is
+    /// `0`: then every share is a full signature. Otherwise use
+    /// `PublicKeySet::combine_signatures`.
+    pub fn into_signature(self) -> Signature {
+        self.0
+    }
+
+    /// Returns `true` if the share is a point in the G2 subgroup other than the identity.
+    ///
+    /// This is a cheap sanity check for shares received from the network; use
+    /// `PublicKeyShare::verify` to check that the share is a signature of a particular message.
+    pub fn is_valid(&self) -> bool {
+        self.0.is_valid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretKeySet;
+    use std::collections::{BTreeSet, HashSet};
+
+    #[test]
+    fn into_signature() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(0, &mut rng);
+        let pk = sk_set.public_keys().public_key().unwrap();
+        let msg = b"Rip and tear, until it's done";
+        for i in 0..3 {
+            let sig = sk_set.secret_key_share(i).sign(msg).into_signature();
+            assert!(pk.verify(&sig, msg));
+        }
+    }
+
+    #[test]
+    fn sort_shares() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let msg = b"Rip and tear, until it's done";
+        let shares: Vec<_> = (0..6)
+            .map(|i| sk_set.secret_key_share(i).sign(msg))
+            .collect();
+
+        let mut sorted = shares.clone();
+        sorted.sort();
+        let mut reversed: Vec<_> = shares.iter().rev().cloned().collect();
+        reversed.sort();
+        assert_eq!(sorted, reversed);
+        for pair in sorted.windows(2) {
+            assert!((pair[0].0).to_bytes() < (pair[1].0).to_bytes());
+        }
+
+        let mut with_duplicates = shares.clone();
+        with_duplicates.extend(shares.iter().cloned());
+        with_duplicates.sort();
+        with_duplicates.dedup();
+        assert_eq!(sorted, with_duplicates);
+    }
+
+    #[test]
+    fn hash_and_order() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let msg = b"Rip and tear, until it's done";
+        let shares: Vec<_> = (0..6)
+            .map(|i| sk_set.secret_key_share(i).sign(msg))
+            .collect();
+
+        // Shares signed again, or decoded, are duplicates.
+        let resigned = (0..6).map(|i| sk_set.secret_key_share(i).sign(msg));
+        let set: HashSet<SignatureShare> = shares.iter().cloned().chain(resigned).collect();
+        assert_eq!(6, set.len());
+
+        let sorted: BTreeSet<SignatureShare> = shares.iter().cloned().collect();
+        let decoded: BTreeSet<SignatureShare> = shares
+            .iter()
+            .map(|share| {
+                let bytes = bincode::serialize(share).expect("failed to serialize");
+                bincode::deserialize(&bytes).expect("failed to deserialize")
+            })
+            .collect();
+        assert!(sorted.iter().eq(decoded.iter()));
+        let set: HashSet<SignatureShare> = sorted.iter().cloned().chain(decoded).collect();
+        assert_eq!(6, set.len());
+    }
+}
@@ -1,20 +1,59 @@
-use crate::util::{clear_scalar, hash_g2, xor_with_hash};
-use crate::{Ciphertext, PublicKey, Signature};
+use crate::g1sig::{PublicKeyG2, SignatureG1};
+use crate::pk::POP_DST;
+use crate::sealed_ciphertext::open_payload;
+use crate::util::{
+    clear_scalar, context_dst, hash_g1, hash_g2, hash_g2_with_dst, xor_with_hash,
+    xor_with_hash_into, xor_with_hash_iter, Redacted,
+};
+use crate::{
+    Ciphertext, Error, MultiCiphertext, PublicKey, ReEncryptedCiphertext, SealedCiphertext,
+    Signature, VerifiedCiphertext,
+};
+use anyhow::{anyhow, Result};
 use bls12_381::{G1Affine, G2Affine, Scalar};
 use ff::Field;
 use group::Curve;
 use rand::distributions::Standard;
 use rand::prelude::*;
 use rand::{thread_rng, RngCore};
+#[cfg(feature = "secrecy")]
+use secrecy::{ExposeSecret, SecretBox, SecretSlice};
+use std::borrow::Borrow;
 use std::fmt;
-use zeroize::Zeroize;
+use std::ops::{Add, AddAssign};
+use subtle::ConditionallySelectable;
+use tiny_keccak::{Hasher, Shake, Xof};
+use zeroize::{Zeroize, Zeroizing};
 
-#[derive(PartialEq, Eq, Clone, Debug)]
+/// Domain separation tag for deriving secret keys from seeds.
+const KEYGEN_DST: &[u8] = b"rust-tc-keygen-v1";
+
+/// The minimum seed length accepted by `SecretKey::from_seed`.
+pub const MIN_SEED_LEN: usize = 16;
+
+/// A secret key.
+///
+/// Its `Debug` and `Display` output is redacted, unless the `debug-secrets` feature is enabled.
+#[derive(PartialEq, Eq, Clone)]
 pub struct SecretKey(pub Scalar); // XXX: Figure out how not to make Scalar pub
 
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if cfg!(feature = "debug-secrets") {
+            f.debug_tuple("SecretKey").field(&self.0).finish()
+        } else {
+            f.debug_tuple("SecretKey").field(&Redacted).finish()
+        }
+    }
+}
+
 impl fmt::Display for SecretKey {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "SecretKey({})", self.0)
+        if cfg!(feature = "debug-secrets") {
+            write!(f, "SecretKey({})", self.0)
+        } else {
+            write!(f, "SecretKey({:?})", Redacted)
+        }
     }
 }
 
@@ -24,6 +63,17 @@ impl Distribution<SecretKey> for Standard {
     }
 }
 
+/// Returns the zero key.
+///
+/// The zero key is only useful as a starting value, e.g. for summing keys: its public key is the
+/// identity and it signs every message with the identity. Use `SecretKey::random` to generate a
+/// key, and `is_zero` to check for this one.
+impl Default for SecretKey {
+    fn default() -> Self {
+        SecretKey::from_scalar(Scalar::zero())
+    }
+}
+
 impl Zeroize for SecretKey {
     fn zeroize(&mut self) {
         clear_scalar(&mut self.0)
@@ -36,6 +86,29 @@ impl Drop for SecretKey {
     }
 }
 
+impl<B: Borrow<SecretKey>> AddAssign<B> for SecretKey {
+    fn add_assign(&mut self, rhs: B) {
+        self.0 += &rhs.borrow().0;
+    }
+}
+
+impl<B: Borrow<SecretKey>> Add<B> for SecretKey {
+    type Output = SecretKey;
+
+    fn add(mut self, rhs: B) -> SecretKey {
+        self += rhs;
+        self
+    }
+}
+
+impl<B: Borrow<SecretKey>> Add<B> for &SecretKey {
+    type Output = SecretKey;
+
+    fn add(self, rhs: B) -> SecretKey {
+        self.clone() + rhs
+    }
+}
+
 impl SecretKey {
     pub fn from_mut(scalar: &mut Scalar) -> Self {
         let sk = SecretKey(*scalar);
@@ -43,34 +116,297 @@ impl SecretKey {
         sk
     }
 
+    /// Returns `true` if this is the zero key, which must not be used for signing.
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
     /// Returns the matching public key.
+    ///
+    /// In debug builds, this panics if the key is zero.
     pub fn public_key(&self) -> PublicKey {
+        debug_assert!(!self.is_zero(), "public key of the zero secret key");
         let g = G1Affine::generator();
         PublicKey(g * self.0)
     }
 
     /// Sign given msg using secret key
+    ///
+    /// In debug builds, this panics if the key is zero.
     pub fn sign<M: AsRef<[u8]>>(&self, msg: M) -> Signature {
+        debug_assert!(!self.is_zero(), "signing with the zero secret key");
         Signature(hash_g2(msg) * self.0)
     }
 
-    pub fn default() -> Self {
-        SecretKey::from_scalar(Scalar::zero())
+    /// Returns a proof of possession of this key: a signature of the public key, under its own
+    /// domain separation tag, see `sign_with_dst`. See `PublicKey::aggregate_checked`.
+    pub fn prove_possession(&self) -> Signature {
+        self.sign_with_dst(POP_DST, self.public_key().to_bytes())
+    }
+
+    /// Signs the message under the domain separation tag `dst`, see `hash_g2_with_dst`.
+    ///
+    /// The signature only verifies with `PublicKey::verify_with_dst` and the same tag.
+    pub fn sign_with_dst<M: AsRef<[u8]>>(&self, dst: &[u8], msg: M) -> Signature {
+        debug_assert!(!self.is_zero(), "signing with the zero secret key");
+        Signature(hash_g2_with_dst(dst, msg) * self.0)
     }
 
+    /// Signs the message in the context of a consensus `epoch` and `view`, which are bound into
+    /// the hash domain. Verify with `PublicKey::verify_with_context`.
+    ///
+    /// An honest node signs at most one message per context. Signatures at different contexts
+    /// never verify for each other, so two valid signatures of different messages at the same
+    /// `(epoch, view)` are a transferable proof that the signer equivocated.
+    pub fn sign_with_context<M: AsRef<[u8]>>(&self, msg: M, epoch: u64, view: u64) -> Signature {
+        self.sign_with_dst(&context_dst(epoch, view), msg)
+    }
+
+    /// Returns the matching public key in G2, for the min-sig layout.
+    pub fn public_key_g2(&self) -> PublicKeyG2 {
+        PublicKeyG2(G2Affine::generator() * self.0)
+    }
+
+    /// Signs the given message with a signature in G1, for the min-sig layout.
+    pub fn sign_g1<M: AsRef<[u8]>>(&self, msg: M) -> SignatureG1 {
+        SignatureG1(hash_g1(msg) * self.0)
+    }
+
+    /// Signs each of the given messages.
+    ///
+    /// The signatures are independent of each other: the result is the same as calling `sign` on
+    /// each message, and each signature verifies on its own. With the `parallel` feature, the
+    /// messages are hashed and signed on multiple threads.
+    pub fn sign_batch(&self, msgs: &[&[u8]]) -> Vec<Signature> {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            msgs.par_iter().map(|msg| self.sign(msg)).collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            msgs.iter().map(|msg| self.sign(msg)).collect()
+        }
+    }
+
+    /// Returns the secret key multiplied by `factor`, e.g. to blind it.
+    ///
+    /// The public key of the result is the public key of `self` multiplied by `factor`.
+    pub fn mul_scalar(&self, factor: &Scalar) -> SecretKey {
+        let mut scalar = self.0 * factor;
+        SecretKey::from_mut(&mut scalar)
+    }
+
+    /// Splits the key into `n` additive parts, all of which are needed to recover it.
+    ///
+    /// The parts are random, except that they sum to this key. Unlike a `SecretKeySet`, there is
+    /// no threshold: any `n - 1` parts reveal nothing about the key. Since signing is linear in the
+    /// key, the signatures of a message by each part also sum to the key's signature of it, so
+    /// the holders can sign jointly without recombining the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    pub fn split<R: RngCore>(&self, n: usize, rng: &mut R) -> Vec<SecretKey> {
+        assert!(n > 0, "cannot split a secret key into 0 parts");
+        let mut parts: Vec<SecretKey> =
+            (1..n).map(|_| SecretKey::from_rngcore(&mut *rng)).collect();
+        let mut last = self.0;
+        for part in &parts {
+            last -= &part.0;
+        }
+        parts.push(SecretKey::from_mut(&mut last));
+        parts
+    }
+
+    /// Recombines the parts returned by `split` into the original key.
+    pub fn combine_additive(parts: &[SecretKey]) -> SecretKey {
+        let mut sum = SecretKey::default();
+        for part in parts {
+            sum += part;
+        }
+        sum
+    }
+
+    /// Derives a child secret key for the given index.
+    ///
+    /// The child is the key multiplied by a hash of the index and this key's public key, so
+    /// `sk.derive_child(i).public_key() == sk.public_key().derive_child(i)`.
+    pub fn derive_child(&self, index: &[u8]) -> SecretKey {
+        let mut scalar = self.0 * self.public_key().child_scalar(index);
+        SecretKey::from_mut(&mut scalar)
+    }
+
+    #[deprecated(note = "use `try_decrypt`, which reports why decryption failed")]
     pub fn decrypt(&self, ct: &Ciphertext) -> Option<Vec<u8>> {
+        self.try_decrypt(ct).ok()
+    }
+
+    /// Decrypts the ciphertext, or returns `Error::InvalidCiphertext` if it isn't valid.
+    pub fn try_decrypt(&self, ct: &Ciphertext) -> std::result::Result<Vec<u8>, Error> {
+        if !ct.verify() {
+            return Err(Error::InvalidCiphertext);
+        }
+        Ok(self.decrypt_unchecked(ct))
+    }
+
+    /// Decrypts the ciphertext like `try_decrypt`, but returns the plaintext in a buffer that is
+    /// overwritten with zeros when dropped.
+    pub fn decrypt_zeroizing(
+        &self,
+        ct: &Ciphertext,
+    ) -> std::result::Result<Zeroizing<Vec<u8>>, Error> {
+        Ok(Zeroizing::new(self.try_decrypt(ct)?))
+    }
+
+    /// Decrypts the ciphertext into the caller's buffer and returns the plaintext's length.
+    ///
+    /// The plaintext is written to the start of `out` and nowhere else, so the caller controls
+    /// where it lives and when it is scrubbed. Returns `Error::InvalidCiphertext` if the
+    /// ciphertext isn't valid and `Error::BufferTooSmall` if `out` can't hold the plaintext; in
+    /// both cases `out` is left unchanged.
+    pub fn decrypt_into(
+        &self,
+        ct: &Ciphertext,
+        out: &mut [u8],
+    ) -> std::result::Result<usize, Error> {
+        if out.len() < ct.v().len() {
+            return Err(Error::BufferTooSmall(ct.v().len()));
+        }
+        if !ct.verify() {
+            return Err(Error::InvalidCiphertext);
+        }
+        xor_with_hash_into(ct.u() * self.0, ct.v(), out);
+        Ok(ct.v().len())
+    }
+
+    /// Decrypts a ciphertext created by `PublicKey::encrypt_with_aad`, or returns
+    /// `Error::InvalidCiphertext` if it isn't valid for the associated data `aad`.
+    pub fn decrypt_with_aad<A: AsRef<[u8]>>(
+        &self,
+        ct: &Ciphertext,
+        aad: A,
+    ) -> std::result::Result<Vec<u8>, Error> {
+        if !ct.verify_with_aad(aad) {
+            return Err(Error::InvalidCiphertext);
+        }
+        Ok(self.decrypt_unchecked(ct))
+    }
+
+    /// Decrypts a message encrypted with `PublicKey::encrypt_sealed`, or returns
+    /// `Error::InvalidCiphertext` if the header isn't valid or the payload was tampered with.
+    pub fn decrypt_sealed(&self, ct: &SealedCiphertext) -> std::result::Result<Vec<u8>, Error> {
+        let key = Zeroizing::new(self.try_decrypt(&ct.header)?);
+        open_payload(&key, &ct.payload)
+    }
+
+    /// Verifies the ciphertext and returns its plaintext as a lazy iterator, or
+    /// `Error::InvalidCiphertext` if it isn't valid.
+    ///
+    /// Unlike `try_decrypt`, this doesn't allocate the whole plaintext: each byte is computed when
+    /// it is read, so large messages can be processed without holding a second copy in memory.
+    pub fn decrypt_stream<'a>(
+        &self,
+        ct: &'a Ciphertext,
+    ) -> std::result::Result<impl Iterator<Item = u8> + 'a, Error> {
+        if !ct.verify() {
+            return Err(Error::InvalidCiphertext);
+        }
+        Ok(xor_with_hash_iter(ct.u() * self.0, ct.v()))
+    }
+
+    /// Decrypts the ciphertext, with the same sequence of operations whether it is valid or not.
+    ///
+    /// `try_decrypt` returns as soon as verification fails, so an attacker who submits ciphertexts
+    /// and measures response times could tell rejected ciphertexts from accepted ones before
+    /// seeing any result. Here the key is always applied and the plaintext always computed; the
+    /// validity check is only acted upon at the end, where an invalid ciphertext's output is
+    /// discarded. The running time still depends on the ciphertext's length, which is public.
+    pub fn decrypt_ct(&self, ct: &Ciphertext) -> Option<Vec<u8>> {
+        let valid = ct.verify_ct();
+        let mut plaintext = self.decrypt_unchecked(ct);
+        for byte in plaintext.iter_mut() {
+            *byte = u8::conditional_select(&0, byte, valid);
+        }
+        if bool::from(valid) {
+            Some(plaintext)
+        } else {
+            None
+        }
+    }
+
+    /// Decrypts a ciphertext that has already been verified, skipping the pairing check.
+    pub fn decrypt_verified(&self, ct: &VerifiedCiphertext) -> Vec<u8> {
+        self.decrypt_unchecked(ct)
+    }
+
+    /// Decrypts the ciphertext without verifying it.
+    ///
+    /// The caller must have checked it with `Ciphertext::verify`: the plaintext of an invalid
+    /// ciphertext is a decryption oracle for whoever forged it. Prefer `decrypt_verified`, which
+    /// has the type system enforce this.
+    pub fn decrypt_unchecked(&self, ct: &Ciphertext) -> Vec<u8> {
+        let g = ct.u() * self.0;
+        xor_with_hash(g, ct.v())
+    }
+
+    /// Decrypts a ciphertext that a threshold group re-encrypted to this key's public key.
+    pub fn decrypt_reencrypted(&self, ct: &ReEncryptedCiphertext) -> Option<Vec<u8>> {
         if !ct.verify() {
             return None;
         }
-        let Ciphertext(ref u, ref v, _) = *ct;
-        let g = u * self.0;
-        Some(xor_with_hash(g, v))
+        let ReEncryptedCiphertext(ref inner, ref masked, ref mask) = *ct;
+        let g = masked - mask * self.0;
+        Some(xor_with_hash(g, inner.v()))
+    }
+
+    /// Decrypts the payload of recipient `index` of a ciphertext created by `encrypt_multi`.
+    ///
+    /// Returns `Error::InvalidCiphertext` if the ciphertext isn't valid or has no payload at
+    /// `index`. A wrong index for this key gives garbage rather than an error: the ciphertext
+    /// doesn't say which payload belongs to which key.
+    pub fn decrypt_multi(
+        &self,
+        ct: &MultiCiphertext,
+        index: usize,
+    ) -> std::result::Result<Vec<u8>, Error> {
+        let payload = ct.payload(index).ok_or(Error::InvalidCiphertext)?;
+        if !ct.verify() {
+            return Err(Error::InvalidCiphertext);
+        }
+        Ok(xor_with_hash(ct.u() * self.0, payload))
     }
 
     pub fn random() -> Self {
         rand::random()
     }
 
+    /// Deterministically derives a secret key from a seed.
+    ///
+    /// The seed is expanded with SHAKE256 under a fixed domain separation tag into 64 bytes,
+    /// which are reduced to a scalar. Unlike `from_bytes`, the seed doesn't need to be a canonical
+    /// scalar encoding, and the derivation is stable across releases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the seed is shorter than `MIN_SEED_LEN` bytes.
+    pub fn from_seed(seed: &[u8]) -> Self {
+        assert!(
+            seed.len() >= MIN_SEED_LEN,
+            "seed must be at least {} bytes",
+            MIN_SEED_LEN
+        );
+        let mut shake = Shake::v256();
+        shake.update(&(KEYGEN_DST.len() as u64).to_be_bytes());
+        shake.update(KEYGEN_DST);
+        shake.update(seed);
+        let mut okm = [0u8; 64];
+        shake.squeeze(&mut okm);
+        let mut scalar = Scalar::from_bytes_wide(&okm);
+        okm.zeroize();
+        SecretKey::from_mut(&mut scalar)
+    }
+
     /// XXX: Don't use this
     pub fn from_raw(bytes: [u64; 4]) -> Self {
         SecretKey(Scalar::from_raw(bytes))
@@ -81,6 +417,51 @@ impl SecretKey {
         SecretKey(Scalar::from_bytes(bytes).unwrap())
     }
 
+    /// Returns the little-endian byte encoding of the secret key.
+    ///
+    /// The caller is responsible for clearing the returned bytes once they are no longer needed.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    /// Decodes a secret key from its big-endian byte encoding, as used by blst, py_ecc and most
+    /// other BLS libraries. Fails if the value is not less than the group order.
+    pub fn from_be_bytes(bytes: &[u8; 32]) -> Result<Self> {
+        let mut le = *bytes;
+        le.reverse();
+        let scalar: Option<Scalar> = Scalar::from_bytes(&le).into();
+        le.zeroize();
+        let mut scalar = scalar.ok_or_else(|| anyhow!("Invalid secret key encoding"))?;
+        Ok(SecretKey::from_mut(&mut scalar))
+    }
+
+    /// Returns the big-endian byte encoding of the secret key, see `from_be_bytes`.
+    ///
+    /// The caller is responsible for clearing the returned bytes once they are no longer needed.
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let mut bytes = self.to_bytes();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Returns the little-endian byte encoding of the secret key, in a buffer that is overwritten
+    /// with zeros when dropped.
+    pub fn to_bytes_zeroizing(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = self.to_bytes();
+        let out = Zeroizing::new(bytes.to_vec());
+        bytes.zeroize();
+        out
+    }
+
+    /// Returns the big-endian byte encoding of the secret key, in a buffer that is overwritten
+    /// with zeros when dropped.
+    pub fn to_be_bytes_zeroizing(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = self.to_be_bytes();
+        let out = Zeroizing::new(bytes.to_vec());
+        bytes.zeroize();
+        out
+    }
+
     /// XXX: Don't use this either
     pub fn from_rngcore(rng: impl RngCore) -> Self {
         use ff::Field;
@@ -93,13 +474,45 @@ impl SecretKey {
     }
 }
 
+#[cfg(feature = "secrecy")]
+impl ExposeSecret<Scalar> for SecretKey {
+    fn expose_secret(&self) -> &Scalar {
+        &self.0
+    }
+}
+
+#[cfg(feature = "secrecy")]
+impl SecretKey {
+    /// Decodes a secret key from its wrapped little-endian byte encoding.
+    pub fn from_secret_bytes(bytes: &SecretBox<[u8; 32]>) -> Result<Self> {
+        let mut scalar: Scalar = Option::from(Scalar::from_bytes(bytes.expose_secret()))
+            .ok_or_else(|| anyhow!("Invalid secret key encoding"))?;
+        Ok(SecretKey::from_mut(&mut scalar))
+    }
+
+    /// Consumes the key and returns its little-endian byte encoding, wrapped so that it is
+    /// cleared when dropped.
+    pub fn into_secret_bytes(self) -> SecretSlice<u8> {
+        let mut bytes = self.to_bytes();
+        let secret = SecretSlice::from(bytes.to_vec());
+        bytes.zeroize();
+        secret
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::SecretKey;
+    use super::{SecretKey, MIN_SEED_LEN};
+    use crate::{Ciphertext, Error, PublicKey, SecretKeyShare};
     use bls12_381::Scalar;
+    use ff::Field;
+    use group::Curve;
     use rand::distributions::Standard;
     use rand::{thread_rng, Rng};
-    use zeroize::Zeroize;
+    use std::convert::TryInto;
+    use std::mem::ManuallyDrop;
+    use std::ptr;
+    use zeroize::{Zeroize, Zeroizing};
 
     #[test]
     fn random() {
@@ -118,7 +531,176 @@ mod tests {
 
     #[test]
     fn default() {
-        assert_eq!(SecretKey::from_scalar(Scalar::zero()), SecretKey::default())
+        let sk = SecretKey::default();
+        assert_eq!(SecretKey::from_scalar(Scalar::zero()), sk);
+        assert!(sk.is_zero());
+        assert!(!SecretKey::random().is_zero());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "zero secret key")]
+    fn zero_key_sign() {
+        SecretKey::default().sign(b"Rip and tear, until it's done");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "zero secret key")]
+    fn zero_key_public_key() {
+        SecretKey::default().public_key();
+    }
+
+    #[test]
+    fn decrypt_stream() {
+        let sk = SecretKey::random();
+        let msg: Vec<u8> = (0..100_000u32).map(|i| i as u8).collect();
+        let mut ct = sk.public_key().encrypt(&msg);
+
+        let streamed: Vec<u8> = sk
+            .decrypt_stream(&ct)
+            .expect("invalid ciphertext")
+            .collect();
+        assert_eq!(sk.try_decrypt(&ct).unwrap(), streamed);
+        assert_eq!(msg, streamed);
+
+        let mut stream = sk.decrypt_stream(&ct).unwrap();
+        assert_eq!(Some(msg[0]), stream.next());
+        assert_eq!(msg.len() - 1, stream.count());
+
+        let mut v = ct.v().to_vec();
+        v[0] ^= 1;
+        ct = Ciphertext::from_parts(*ct.u(), v, *ct.w());
+        assert!(sk.decrypt_stream(&ct).is_err());
+    }
+
+    #[test]
+    fn decrypt_zeroizing_and_into() {
+        let sk = SecretKey::random();
+        let msg = b"Rip and tear, until it's done";
+        let ct = sk.public_key().encrypt(msg);
+        let expected = sk.try_decrypt(&ct).unwrap();
+        assert_eq!(expected, *sk.decrypt_zeroizing(&ct).unwrap());
+
+        let mut out = [0xffu8; 64];
+        assert_eq!(Ok(msg.len()), sk.decrypt_into(&ct, &mut out));
+        assert_eq!(&expected[..], &out[..msg.len()]);
+        assert!(out[msg.len()..].iter().all(|b| *b == 0xff));
+
+        let mut short = [0u8; 8];
+        assert_eq!(
+            Err(Error::BufferTooSmall(msg.len())),
+            sk.decrypt_into(&ct, &mut short)
+        );
+        assert_eq!([0u8; 8], short);
+
+        let mut v = ct.v().to_vec();
+        v[0] ^= 1;
+        let invalid = Ciphertext::from_parts(*ct.u(), v, *ct.w());
+        assert_eq!(
+            Err(Error::InvalidCiphertext),
+            sk.decrypt_zeroizing(&invalid)
+        );
+        assert_eq!(
+            Err(Error::InvalidCiphertext),
+            sk.decrypt_into(&invalid, &mut out)
+        );
+    }
+
+    #[test]
+    fn decrypt_ct() {
+        let sk = SecretKey::random();
+        for msg in [&b""[..], &b"Rip and tear, until it's done"[..]].iter() {
+            let mut ct = sk.public_key().encrypt(msg);
+            assert_eq!(sk.try_decrypt(&ct).ok(), sk.decrypt_ct(&ct));
+            assert_eq!(Some(msg.to_vec()), sk.decrypt_ct(&ct));
+
+            let other = SecretKey::random();
+            assert_eq!(other.try_decrypt(&ct).ok(), other.decrypt_ct(&ct));
+
+            ct = Ciphertext::from_parts(*ct.u(), ct.v().to_vec(), -ct.w());
+            assert_eq!(None, sk.decrypt_ct(&ct));
+            assert_eq!(sk.try_decrypt(&ct).ok(), sk.decrypt_ct(&ct));
+        }
+    }
+
+    #[test]
+    fn decrypt_verified() {
+        let sk = SecretKey::random();
+        let msg = b"Rip and tear, until it's done";
+        let ct = sk.public_key().encrypt(msg);
+        let verified = ct.clone().into_verified().expect("invalid ciphertext");
+        assert_eq!(ct, *verified);
+        assert_eq!(sk.try_decrypt(&ct).unwrap(), sk.decrypt_verified(&verified));
+        assert_eq!(sk.decrypt_verified(&verified), sk.decrypt_unchecked(&ct));
+
+        let share = SecretKeyShare::from_sk(sk);
+        assert_eq!(
+            share.try_decrypt_share(&ct).unwrap(),
+            share.decrypt_share_verified(&verified)
+        );
+        assert_eq!(
+            share.decrypt_share_verified(&verified),
+            share.decrypt_share_unchecked(&ct)
+        );
+
+        let mut v = ct.v().to_vec();
+        v[0] ^= 1;
+        let invalid = Ciphertext::from_parts(*ct.u(), v, *ct.w());
+        assert_eq!(Err(Error::InvalidCiphertext), invalid.into_verified());
+    }
+
+    #[test]
+    fn try_decrypt_invalid() {
+        let sk = SecretKey::random();
+        let msg = b"Rip and tear, until it's done";
+        let mut ct = sk.public_key().encrypt(msg);
+        assert_eq!(Ok(msg.to_vec()), sk.try_decrypt(&ct));
+
+        let mut v = ct.v().to_vec();
+        v[0] ^= 1;
+        ct = Ciphertext::from_parts(*ct.u(), v, *ct.w());
+        assert_eq!(Err(Error::InvalidCiphertext), sk.try_decrypt(&ct));
+        let share = SecretKeyShare::from_sk(sk);
+        assert_eq!(Err(Error::InvalidCiphertext), share.try_decrypt_share(&ct));
+    }
+
+    #[test]
+    fn sign_with_context() {
+        let sk = SecretKey::random();
+        let pk = sk.public_key();
+        let msg = b"Rip and tear, until it's done";
+
+        let sig = sk.sign_with_context(msg, 3, 7);
+        assert_eq!(sig, sk.sign_with_context(msg, 3, 7));
+        assert!(pk.verify_with_context(&sig, msg, 3, 7));
+        assert!(!pk.verify(&sig, msg));
+        for (epoch, view) in [(3, 8), (4, 7), (7, 3), (0, 0)] {
+            assert_ne!(sig, sk.sign_with_context(msg, epoch, view));
+            assert!(!pk.verify_with_context(&sig, msg, epoch, view));
+        }
+
+        // Two valid signatures of different messages at the same context prove equivocation.
+        let other = sk.sign_with_context(b"Other msg", 3, 7);
+        assert!(pk.verify_with_context(&other, b"Other msg", 3, 7));
+        assert_ne!(sig, other);
+    }
+
+    #[test]
+    fn sign_batch() {
+        let sk = SecretKey::random();
+        let pk = sk.public_key();
+        let msgs: Vec<Vec<u8>> = (0..20u32).map(|i| i.to_be_bytes().to_vec()).collect();
+        let msgs: Vec<&[u8]> = msgs.iter().map(Vec::as_slice).collect();
+
+        let sigs = sk.sign_batch(&msgs);
+        assert_eq!(msgs.len(), sigs.len());
+        for (msg, sig) in msgs.iter().zip(&sigs) {
+            assert_eq!(sk.sign(msg), *sig);
+            assert!(pk.verify(sig, msg));
+        }
+        assert!(!pk.verify(&sigs[0], msgs[1]));
+        assert!(sk.sign_batch(&[]).is_empty());
     }
 
     #[test]
@@ -131,6 +713,166 @@ mod tests {
         assert!(pk.verify(&sig, msg));
     }
 
+    #[test]
+    fn derive_child() {
+        let sk = SecretKey::random();
+        let pk = sk.public_key();
+        let msg = b"Rip and tear, until it's done";
+
+        let long_index = vec![7u8; 4096];
+        for index in [&b""[..], &b"sign blocks"[..], &long_index[..]].iter() {
+            let child_sk = sk.derive_child(index);
+            let child_pk = pk.derive_child(index);
+            assert_eq!(child_pk, child_sk.public_key());
+            assert_ne!(pk, child_pk);
+
+            let sig = child_sk.sign(msg);
+            assert!(child_pk.verify(&sig, msg));
+            assert!(!pk.verify(&sig, msg));
+        }
+
+        let blocks = sk.derive_child(b"sign blocks");
+        let attestations = sk.derive_child(b"sign attestations");
+        assert_ne!(blocks, attestations);
+    }
+
+    #[test]
+    fn add() {
+        let sk1 = SecretKey::random();
+        let sk2 = SecretKey::random();
+        let sum = &sk1 + &sk2;
+        assert_eq!(sum.public_key(), sk1.public_key() + sk2.public_key());
+
+        let msg = b"Rip and tear, until it's done";
+        let sig = sum.sign(msg);
+        assert!((sk1.public_key() + sk2.public_key()).verify(&sig, msg));
+
+        let mut acc = SecretKey::default();
+        acc += &sk1;
+        acc += sk2;
+        assert_eq!(sum, acc);
+    }
+
+    #[test]
+    fn split() {
+        let mut rng = thread_rng();
+        let sk = SecretKey::random();
+        let msg = b"Rip and tear, until it's done";
+
+        for n in 1..4 {
+            let parts = sk.split(n, &mut rng);
+            assert_eq!(n, parts.len());
+            let combined = SecretKey::combine_additive(&parts);
+            assert_eq!(sk, combined);
+            assert_eq!(sk.sign(msg), combined.sign(msg));
+
+            let partial_sigs: Vec<_> = parts.iter().map(|part| part.sign(msg)).collect();
+            let sig = crate::aggregate(&partial_sigs).unwrap();
+            assert_eq!(sk.sign(msg), sig);
+            assert!(sk.public_key().verify(&sig, msg));
+        }
+
+        let parts = sk.split(2, &mut rng);
+        assert_ne!(sk, parts[0]);
+        assert_ne!(sk, SecretKey::combine_additive(&parts[..1]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_zero() {
+        SecretKey::random().split(0, &mut thread_rng());
+    }
+
+    #[test]
+    fn mul_scalar() {
+        let sk = SecretKey::random();
+        let factor = Scalar::random(thread_rng());
+        let blinded = sk.mul_scalar(&factor);
+        assert_eq!(PublicKey(sk.public_key().0 * factor), blinded.public_key());
+        assert_eq!(SecretKey::default(), sk.mul_scalar(&Scalar::zero()));
+    }
+
+    #[test]
+    fn be_bytes() {
+        // The first Ethereum interop validator key, as generated by blst.
+        let sk_bytes = [
+            0x25, 0x29, 0x5f, 0x0d, 0x1d, 0x59, 0x2a, 0x90, 0xb3, 0x33, 0xe2, 0x6e, 0x85, 0x14,
+            0x97, 0x08, 0x20, 0x8e, 0x9f, 0x8e, 0x8b, 0xc1, 0x8f, 0x6c, 0x77, 0xbd, 0x62, 0xf8,
+            0xad, 0x7a, 0x68, 0x66,
+        ];
+        let pk_bytes = [
+            0xa9, 0x9a, 0x76, 0xed, 0x77, 0x96, 0xf7, 0xbe, 0x22, 0xd5, 0xb7, 0xe8, 0x5d, 0xee,
+            0xb7, 0xc5, 0x67, 0x7e, 0x88, 0xe5, 0x11, 0xe0, 0xb3, 0x37, 0x61, 0x8f, 0x8c, 0x4e,
+            0xb6, 0x13, 0x49, 0xb4, 0xbf, 0x2d, 0x15, 0x3f, 0x64, 0x9f, 0x7b, 0x53, 0x35, 0x9f,
+            0xe8, 0xb9, 0x4a, 0x38, 0xe4, 0x4c,
+        ];
+        let sk = SecretKey::from_be_bytes(&sk_bytes).expect("failed to decode");
+        assert_eq!(
+            &pk_bytes[..],
+            &sk.public_key().0.to_affine().to_compressed()[..]
+        );
+        assert_eq!(sk_bytes, sk.to_be_bytes());
+
+        let mut le_bytes = sk_bytes;
+        le_bytes.reverse();
+        assert_eq!(le_bytes, sk.to_bytes());
+
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(
+            SecretKey::from_scalar(Scalar::one()),
+            SecretKey::from_be_bytes(&one).unwrap()
+        );
+        assert!(SecretKey::from_be_bytes(&[0xff; 32]).is_err());
+    }
+
+    #[test]
+    fn zeroizing_bytes() {
+        let sk = SecretKey::random();
+        let bytes: Zeroizing<Vec<u8>> = sk.to_bytes_zeroizing();
+        assert_eq!(&sk.to_bytes()[..], &bytes[..]);
+        let bytes: [u8; 32] = bytes.as_slice().try_into().unwrap();
+        assert_eq!(sk, SecretKey::from_bytes(&bytes));
+
+        let be_bytes: Zeroizing<Vec<u8>> = sk.to_be_bytes_zeroizing();
+        let be_bytes: [u8; 32] = be_bytes.as_slice().try_into().unwrap();
+        assert_eq!(sk, SecretKey::from_be_bytes(&be_bytes).unwrap());
+    }
+
+    #[test]
+    fn from_seed() {
+        // Known answers, little-endian scalar encodings. These must never change.
+        let seed: Vec<u8> = (0..32).collect();
+        let expected = [
+            14, 153, 164, 155, 35, 134, 222, 83, 9, 90, 74, 137, 29, 130, 21, 195, 89, 155, 84,
+            159, 24, 15, 244, 102, 208, 117, 211, 115, 142, 223, 17, 78,
+        ];
+        assert_eq!(
+            SecretKey::from_bytes(&expected),
+            SecretKey::from_seed(&seed)
+        );
+
+        let expected = [
+            187, 221, 232, 154, 65, 14, 28, 165, 196, 79, 225, 48, 201, 1, 197, 136, 72, 12, 122,
+            231, 109, 251, 232, 172, 143, 187, 175, 248, 120, 198, 79, 80,
+        ];
+        assert_eq!(
+            SecretKey::from_bytes(&expected),
+            SecretKey::from_seed(&[0u8; MIN_SEED_LEN])
+        );
+
+        let long_seed = vec![42u8; 1024];
+        let sk = SecretKey::from_seed(&long_seed);
+        assert_eq!(sk, SecretKey::from_seed(&long_seed));
+        assert_ne!(sk, SecretKey::from_seed(&long_seed[..MIN_SEED_LEN]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_short_seed() {
+        SecretKey::from_seed(&[0u8; MIN_SEED_LEN - 1]);
+    }
+
     #[test]
     fn test_zeroize() {
         let zero_sk = SecretKey::from_mut(&mut Scalar::zero());
@@ -141,4 +883,31 @@ mod tests {
         sk.zeroize();
         assert_eq!(zero_sk, sk);
     }
+
+    #[test]
+    fn drop_zeroizes() {
+        let mut sk = ManuallyDrop::new(SecretKey::random());
+        unsafe { ptr::drop_in_place(&mut *sk) };
+        assert_eq!(Scalar::zero(), sk.0);
+    }
+
+    #[cfg(feature = "secrecy")]
+    #[test]
+    fn secret_bytes_round_trip() {
+        use secrecy::{ExposeSecret, SecretBox};
+
+        let sk = SecretKey::random();
+        let expected = sk.clone();
+        assert_eq!(&expected.0, sk.expose_secret());
+
+        let bytes = sk.into_secret_bytes();
+        assert_eq!(&expected.to_bytes()[..], bytes.expose_secret());
+
+        let boxed =
+            SecretBox::<[u8; 32]>::init_with_mut(|b| b.copy_from_slice(bytes.expose_secret()));
+        assert_eq!(expected, SecretKey::from_secret_bytes(&boxed).unwrap());
+
+        let invalid = SecretBox::new(Box::new([0xff; 32]));
+        assert!(SecretKey::from_secret_bytes(&invalid).is_err());
+    }
 }
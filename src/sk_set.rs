@@ -1,10 +1,21 @@
-use crate::util::into_scalar_plus_1;
-use crate::{IntoScalar, Poly, PublicKeySet, SecretKey, SecretKeyShare};
-use anyhow::Result;
+use crate::g1sig::PublicKeySetG2;
+use crate::util::{clear_scalar, into_scalar_plus_1};
+use crate::{IntoScalar, Poly, PublicKeySet, SecretKey, SecretKeyShare, ShareEnvelope};
+use anyhow::{anyhow, bail, Result};
+use bls12_381::{G2Affine, Scalar};
+use ff::Field;
 use rand::Rng;
 use rand_core::RngCore;
+#[cfg(feature = "secrecy")]
+use secrecy::{ExposeSecret, SecretSlice};
+use zeroize::{Zeroize, Zeroizing};
+
+/// The length of a single encoded coefficient or share.
+const SCALAR_SIZE: usize = 32;
 
 /// A secret key and an associated set of secret key shares.
+///
+/// Its `Debug` output is redacted, unless the `debug-secrets` feature is enabled.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct SecretKeySet {
     /// The coefficients of a polynomial whose value at `0` is the "master key", and value at
@@ -38,6 +49,19 @@ impl SecretKeySet {
         Poly::try_random(threshold, rng).map(SecretKeySet::from)
     }
 
+    /// Creates the set with the given polynomial coefficients, lowest degree first: `coeffs[0]` is
+    /// the master key, and the threshold is `coeffs.len() - 1`.
+    ///
+    /// This is for reproducible tests and fixtures. The keys are only as secret as the
+    /// coefficients, so real key sets must be created with `random` or a DKG. Fails if `coeffs` is
+    /// empty.
+    pub fn from_coefficients(coeffs: &[Scalar]) -> Result<Self> {
+        if coeffs.is_empty() {
+            bail!("Cannot create a secret key set without coefficients")
+        }
+        Ok(SecretKeySet::from(Poly::from(coeffs.to_vec())))
+    }
+
     /// Returns the threshold `t`: any set of `t + 1` signature shares can be combined into a full
     /// signature.
     pub fn threshold(&self) -> usize {
@@ -50,6 +74,11 @@ impl SecretKeySet {
         SecretKeyShare::from_mut(&mut scalar)
     }
 
+    /// Signs `msg` with the `i`-th secret key share, in a `ShareEnvelope`.
+    pub fn share_envelope<M: AsRef<[u8]>>(&self, i: usize, msg: M) -> ShareEnvelope {
+        ShareEnvelope::new(&self.public_keys(), i, &self.secret_key_share(i), msg)
+    }
+
     /// Returns the corresponding public key set. That information can be shared publicly.
     pub fn public_keys(&self) -> PublicKeySet {
         PublicKeySet {
@@ -57,6 +86,109 @@ impl SecretKeySet {
         }
     }
 
+    /// Returns the corresponding public key set in G2, for the min-sig layout.
+    pub fn public_keys_g2(&self) -> PublicKeySetG2 {
+        let g = G2Affine::generator();
+        PublicKeySetG2 {
+            commit: self.poly.coeff.iter().map(|c| g * c).collect(),
+        }
+    }
+
+    /// Encodes the whole set as the concatenation of its polynomial's coefficients.
+    ///
+    /// # Security
+    ///
+    /// The encoding contains the master secret key and every share. Anyone who obtains it can sign
+    /// and decrypt on behalf of the whole group. It is meant only for a trusted dealer to persist
+    /// or back up the set; to hand out key shares, use `shares_to_bytes` instead. The returned
+    /// buffer is overwritten with zeros when dropped.
+    pub fn to_bytes_secret(&self) -> Zeroizing<Vec<u8>> {
+        // Allocate the exact length up front, so that the bytes are never reallocated.
+        let mut bytes = Zeroizing::new(Vec::with_capacity(self.poly.coeff.len() * SCALAR_SIZE));
+        for c in &self.poly.coeff {
+            let mut c_bytes = c.to_bytes();
+            bytes.extend_from_slice(&c_bytes);
+            c_bytes.zeroize();
+        }
+        bytes
+    }
+
+    /// Decodes a set encoded with `to_bytes_secret`.
+    ///
+    /// # Security
+    ///
+    /// See `to_bytes_secret`: the input is the full group secret.
+    pub fn from_bytes_secret(bytes: &[u8]) -> Result<Self> {
+        if bytes.is_empty() || bytes.len() % SCALAR_SIZE != 0 {
+            bail!(
+                "Invalid secret key set encoding length {}, expected a non-zero multiple of {}",
+                bytes.len(),
+                SCALAR_SIZE
+            )
+        }
+        let mut coeff = Vec::with_capacity(bytes.len() / SCALAR_SIZE);
+        let mut buf = [0u8; SCALAR_SIZE];
+        for chunk in bytes.chunks(SCALAR_SIZE) {
+            buf.copy_from_slice(chunk);
+            match Option::<Scalar>::from(Scalar::from_bytes(&buf)) {
+                Some(c) => coeff.push(c),
+                None => {
+                    buf.zeroize();
+                    coeff.iter_mut().for_each(clear_scalar);
+                    bail!("Invalid coefficient encoding in secret key set")
+                }
+            }
+        }
+        buf.zeroize();
+        Ok(SecretKeySet::from(Poly::from(coeff)))
+    }
+
+    /// Encodes the first `n` secret key shares, for distribution to their holders. Element `i` of
+    /// the result is the encoding of `secret_key_share(i)`.
+    ///
+    /// Unlike `to_bytes_secret`, no single entry reveals more than one share. Each entry is
+    /// overwritten with zeros when dropped.
+    pub fn shares_to_bytes(&self, n: usize) -> Vec<Zeroizing<[u8; SCALAR_SIZE]>> {
+        (0..n)
+            .map(|i| Zeroizing::new(self.secret_key_share(i).to_bytes()))
+            .collect()
+    }
+
+    /// Returns a refreshed set with the same master key, in which the `index`-th share has been
+    /// rotated.
+    ///
+    /// A random polynomial that is zero at `0` is added to the set's polynomial. This leaves the
+    /// master key unchanged but re-randomizes every share, so the previous `index`-th share (and
+    /// any other leaked share) can no longer be combined with the new ones. All share holders must
+    /// switch to their new shares, and the new `PublicKeySet` must be published.
+    ///
+    /// This only helps if the attacker has obtained no more than `threshold` shares before the
+    /// rotation, i.e. at least `threshold + 1` share holders were honest. Fails if the threshold is
+    /// `0`, since then every share is the master key itself.
+    pub fn reshare_single<R: RngCore>(&self, index: usize, rng: &mut R) -> Result<SecretKeySet> {
+        let threshold = self.threshold();
+        if threshold == 0 {
+            bail!("Cannot rotate a share of a set with threshold 0")
+        }
+        let x = into_scalar_plus_1(index);
+        loop {
+            // `mask(x) = x * r(x)` is zero at `0`, so the master key is not affected.
+            let mut mask = Poly::try_random(threshold - 1, rng)? * Poly::identity();
+            if mask.evaluate(x).is_zero() {
+                mask.zeroize();
+                continue;
+            }
+            let poly = &self.poly + &mask;
+            mask.zeroize();
+            return Ok(SecretKeySet::from(poly));
+        }
+    }
+
+    /// Returns `true` if `pk_set` is the public key set corresponding to this set.
+    pub fn matches(&self, pk_set: &PublicKeySet) -> bool {
+        self.poly.commitment() == pk_set.commit
+    }
+
     /// Returns the secret master key.
     #[cfg(test)]
     fn secret_key(&self) -> SecretKey {
@@ -64,3 +196,232 @@ impl SecretKeySet {
         SecretKey::from_mut(&mut fr)
     }
 }
+
+#[cfg(feature = "secrecy")]
+impl ExposeSecret<Poly> for SecretKeySet {
+    fn expose_secret(&self) -> &Poly {
+        &self.poly
+    }
+}
+
+#[cfg(feature = "secrecy")]
+impl SecretKeySet {
+    /// Decodes a set from its wrapped `to_bytes_secret` encoding.
+    pub fn from_secret_bytes(bytes: &SecretSlice<u8>) -> Result<Self> {
+        SecretKeySet::from_bytes_secret(bytes.expose_secret())
+    }
+
+    /// Consumes the set and returns its `to_bytes_secret` encoding, wrapped so that it is cleared
+    /// when dropped.
+    pub fn into_secret_bytes(self) -> SecretSlice<u8> {
+        let mut bytes = self.to_bytes_secret();
+        SecretSlice::from(std::mem::take(&mut *bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BivarPoly;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn verify_secret_key_share() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+
+        for i in 0..5 {
+            let share = sk_set.secret_key_share(i);
+            assert!(pk_set.verify_secret_key_share(i, &share));
+            assert!(!pk_set.verify_secret_key_share(i + 1, &share));
+        }
+
+        let other_pk_set = SecretKeySet::random(2, &mut rng).public_keys();
+        assert!(!other_pk_set.verify_secret_key_share(0, &sk_set.secret_key_share(0)));
+        // The zero share is rejected, rather than tripping the zero key check in `public_key`.
+        assert!(!pk_set.verify_secret_key_share(0, &SecretKeyShare::default()));
+    }
+
+    #[test]
+    fn secret_bytes_round_trip() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(3, &mut rng);
+        let bytes: Zeroizing<Vec<u8>> = sk_set.to_bytes_secret();
+        assert_eq!(4 * SCALAR_SIZE, bytes.len());
+
+        let decoded = SecretKeySet::from_bytes_secret(&bytes).expect("failed to decode");
+        assert_eq!(sk_set, decoded);
+        assert_eq!(sk_set.public_keys(), decoded.public_keys());
+
+        assert!(SecretKeySet::from_bytes_secret(&[]).is_err());
+        assert!(SecretKeySet::from_bytes_secret(&bytes[1..]).is_err());
+        assert!(SecretKeySet::from_bytes_secret(&[0xff; SCALAR_SIZE]).is_err());
+    }
+
+    /// Returns pairs of the `Debug` or `Display` output of secret values, and a secret that the
+    /// output would contain if it weren't redacted.
+    fn secret_outputs() -> Vec<(String, String)> {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(1, &mut rng);
+        let sk = sk_set.secret_key();
+        let share = sk_set.secret_key_share(0);
+        let bi_poly = BivarPoly::random(1);
+        let sk_secret = format!("{:?}", sk.0);
+        let share_secret = format!("{:?}", share.as_secret_key().0);
+        let poly_secret = format!("{:?}", sk_set.poly.coeff[1]);
+        let bi_poly_secret = format!("{:?}", bi_poly.evaluate(0, 0));
+        vec![
+            (format!("{:?}", sk), sk_secret.clone()),
+            (format!("{}", sk), sk_secret),
+            (format!("{:?}", share), share_secret.clone()),
+            (format!("{}", share), share_secret),
+            (format!("{:?}", sk_set.poly), poly_secret.clone()),
+            (format!("{:?}", sk_set), poly_secret),
+            (format!("{:?}", bi_poly), bi_poly_secret),
+        ]
+    }
+
+    #[cfg(not(feature = "debug-secrets"))]
+    #[test]
+    fn debug_redacted() {
+        for (output, secret) in secret_outputs() {
+            assert!(output.contains("<redacted>"), "{}", output);
+            assert!(!output.contains(&secret), "{}", output);
+        }
+    }
+
+    #[cfg(feature = "debug-secrets")]
+    #[test]
+    fn debug_revealed() {
+        for (output, secret) in secret_outputs() {
+            assert!(!output.contains("<redacted>"), "{}", output);
+            assert!(output.contains(&secret), "{}", output);
+        }
+    }
+
+    #[test]
+    fn from_coefficients() {
+        let coeffs: Vec<Scalar> = [1u64, 2, 3].iter().map(|c| Scalar::from(*c)).collect();
+        let sk_set = SecretKeySet::from_coefficients(&coeffs).unwrap();
+        assert_eq!(2, sk_set.threshold());
+        assert_eq!(Scalar::one(), sk_set.secret_key().0);
+        // Share `i` is `1 + 2x + 3x^2` at `x = i + 1`.
+        for (i, expected) in [6u64, 17, 34, 57].iter().enumerate() {
+            let share = sk_set.secret_key_share(i);
+            assert_eq!(Scalar::from(*expected), share.as_secret_key().0);
+        }
+        assert_eq!(
+            sk_set.public_keys(),
+            SecretKeySet::from_coefficients(&coeffs)
+                .unwrap()
+                .public_keys()
+        );
+
+        assert!(SecretKeySet::from_coefficients(&[]).is_err());
+    }
+
+    #[test]
+    fn shares_bytes_round_trip() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+
+        let shares: Vec<Zeroizing<[u8; SCALAR_SIZE]>> = sk_set.shares_to_bytes(5);
+        assert_eq!(5, shares.len());
+        for (i, bytes) in shares.iter().enumerate() {
+            let share = SecretKeyShare::from_bytes(bytes).expect("failed to decode share");
+            assert_eq!(sk_set.secret_key_share(i), share);
+            assert!(pk_set.verify_secret_key_share(i, &share));
+
+            let be_share = SecretKeyShare::from_be_bytes(&share.to_be_bytes()).unwrap();
+            assert_eq!(share, be_share);
+            assert_eq!(&share.to_be_bytes()[..], &share.to_be_bytes_zeroizing()[..]);
+        }
+    }
+
+    #[cfg(feature = "secrecy")]
+    #[test]
+    fn secret_bytes_wrapped_round_trip() {
+        use secrecy::{ExposeSecret, SecretBox};
+
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let share = sk_set.secret_key_share(3);
+        let expected = share.clone();
+
+        let bytes = share.into_secret_bytes();
+        let boxed = SecretBox::<[u8; SCALAR_SIZE]>::init_with_mut(|b| {
+            b.copy_from_slice(bytes.expose_secret())
+        });
+        let decoded = SecretKeyShare::from_secret_bytes(&boxed).unwrap();
+        assert_eq!(expected, decoded);
+        assert_eq!(expected.expose_secret(), decoded.expose_secret());
+
+        let expected = sk_set.clone();
+        assert_eq!(&expected.poly, sk_set.expose_secret());
+        let bytes = sk_set.into_secret_bytes();
+        assert_eq!(expected, SecretKeySet::from_secret_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    fn add_shares() {
+        let mut rng = rand::thread_rng();
+        let sk_set1 = SecretKeySet::random(2, &mut rng);
+        let sk_set2 = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set1.public_keys().combine(sk_set2.public_keys());
+
+        for i in 0..5 {
+            let share = sk_set1.secret_key_share(i) + sk_set2.secret_key_share(i);
+            assert!(pk_set.verify_secret_key_share(i, &share));
+        }
+    }
+
+    #[test]
+    fn reshare_single() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        let leaked = sk_set.secret_key_share(1);
+
+        let new_sk_set = sk_set
+            .reshare_single(1, &mut rng)
+            .expect("failed to reshare");
+        let new_pk_set = new_sk_set.public_keys();
+        assert_eq!(
+            pk_set.public_key().unwrap(),
+            new_pk_set.public_key().unwrap()
+        );
+        assert_eq!(sk_set.threshold(), new_sk_set.threshold());
+        assert_ne!(leaked, new_sk_set.secret_key_share(1));
+
+        let msg = b"Rip and tear, until it's done";
+
+        // Fresh shares still combine into a valid signature under the master key.
+        let fresh: BTreeMap<_, _> = (0..3)
+            .map(|i| (i, new_sk_set.secret_key_share(i).sign(msg)))
+            .collect();
+        let sig = new_pk_set.combine_signatures(&fresh).unwrap();
+        assert!(new_pk_set.public_key().unwrap().verify(&sig, msg));
+
+        // The leaked share doesn't combine with fresh ones.
+        let mut mixed = fresh;
+        mixed.insert(1, leaked.sign(msg));
+        let sig = new_pk_set.combine_signatures(&mixed).unwrap();
+        assert!(!new_pk_set.public_key().unwrap().verify(&sig, msg));
+
+        let sk_set = SecretKeySet::random(0, &mut rng);
+        assert!(sk_set.reshare_single(0, &mut rng).is_err());
+    }
+
+    #[test]
+    fn matches() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        assert!(sk_set.matches(&sk_set.public_keys()));
+
+        let other = SecretKeySet::random(2, &mut rng);
+        assert!(!sk_set.matches(&other.public_keys()));
+        assert!(!other.matches(&sk_set.public_keys()));
+    }
+}
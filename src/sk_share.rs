@@ -1,36 +1,412 @@
-use crate::{Ciphertext, DecryptionShare, PublicKeyShare, SecretKey, SignatureShare};
+use crate::coin::coin_message;
+use crate::g1sig::{PublicKeyShareG2, SignatureShareG1};
+use crate::util::Redacted;
+use crate::{
+    Ciphertext, CoinShare, DecryptionShare, Error, IntoScalar, ProvenDecryptionShare, PublicKey,
+    PublicKeySet, PublicKeyShare, ReKeyShare, SecretKey, SignatureShare, VerifiedCiphertext,
+};
+use anyhow::{anyhow, Result};
 use bls12_381::{G1Affine, Scalar};
+use ff::Field;
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+#[cfg(feature = "secrecy")]
+use secrecy::{ExposeSecret, SecretBox, SecretSlice};
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::{Add, AddAssign};
+use zeroize::{Zeroize, Zeroizing};
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+/// A secret key share.
+///
+/// Its `Debug` and `Display` output is redacted, so shares can't leak into logs, unless the
+/// `debug-secrets` feature is enabled; use `reveal` to print the key on purpose.
+#[derive(Clone, PartialEq, Eq)]
 pub struct SecretKeyShare(SecretKey);
 
+impl fmt::Debug for SecretKeyShare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if cfg!(feature = "debug-secrets") {
+            f.debug_tuple("SecretKeyShare").field(&(self.0).0).finish()
+        } else {
+            f.debug_tuple("SecretKeyShare").field(&Redacted).finish()
+        }
+    }
+}
+
+impl fmt::Display for SecretKeyShare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if cfg!(feature = "debug-secrets") {
+            write!(f, "SecretKeyShare({})", (self.0).0)
+        } else {
+            write!(f, "SecretKeyShare({:?})", Redacted)
+        }
+    }
+}
+
+impl Zeroize for SecretKeyShare {
+    fn zeroize(&mut self) {
+        self.0.zeroize()
+    }
+}
+
+impl<B: Borrow<SecretKeyShare>> AddAssign<B> for SecretKeyShare {
+    fn add_assign(&mut self, rhs: B) {
+        self.0 += &rhs.borrow().0;
+    }
+}
+
+impl<B: Borrow<SecretKeyShare>> Add<B> for SecretKeyShare {
+    type Output = SecretKeyShare;
+
+    fn add(mut self, rhs: B) -> SecretKeyShare {
+        self += rhs;
+        self
+    }
+}
+
+impl<B: Borrow<SecretKeyShare>> Add<B> for &SecretKeyShare {
+    type Output = SecretKeyShare;
+
+    fn add(self, rhs: B) -> SecretKeyShare {
+        self.clone() + rhs
+    }
+}
+
+impl Default for SecretKeyShare {
+    /// Returns the zero share, like `SecretKey::default`. It is a placeholder, not a usable key:
+    /// `is_zero` detects it.
+    fn default() -> Self {
+        SecretKeyShare(SecretKey::default())
+    }
+}
+
+impl From<SecretKey> for SecretKeyShare {
+    /// Wraps the key without copying it, e.g. for the single-party case with threshold `0`.
+    fn from(sk: SecretKey) -> Self {
+        SecretKeyShare(sk)
+    }
+}
+
 impl SecretKeyShare {
     pub fn from_sk(sk: SecretKey) -> Self {
         SecretKeyShare(sk)
     }
 
+    /// Returns the share as a secret key, without copying it.
+    pub fn as_secret_key(&self) -> &SecretKey {
+        &self.0
+    }
+
+    /// Returns a non-redacted debug string. Unlike the `Debug` and `Display` implementations, it
+    /// contains the secret key share.
+    pub fn reveal(&self) -> String {
+        format!("SecretKeyShare({})", (self.0).0)
+    }
+
+    #[deprecated(note = "use `random`, which takes an explicit random number generator")]
     pub fn new() -> Self {
         SecretKeyShare(SecretKey::random())
     }
 
+    /// Creates a random secret key share, e.g. for tests or a trivial single-party set.
+    ///
+    /// Shares that are meant to be combined must come from a `SecretKeySet` or a DKG instead.
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut scalar = Scalar::random(rng);
+        SecretKeyShare::from_mut(&mut scalar)
+    }
+
+    /// Returns `true` if this is the zero share, e.g. the `Default`, which must not be used.
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
     pub fn public_key_share(&self) -> PublicKeyShare {
         PublicKeyShare(self.0.public_key())
     }
 
+    /// Returns `true` if this is the `index`-th share of the key set `pk_set`.
+    ///
+    /// A share received out of band, e.g. from a dealer, should be checked against the published
+    /// key set before it is used, so that distribution errors are caught early. This is
+    /// `pk_set.verify_secret_key_share(index, self)`.
+    pub fn verify_against<T: IntoScalar>(&self, pk_set: &PublicKeySet, index: T) -> bool {
+        pk_set.verify_secret_key_share(index, self)
+    }
+
     /// Signs the given message.
     pub fn sign<M: AsRef<[u8]>>(&self, msg: M) -> SignatureShare {
         SignatureShare(self.0.sign(msg))
     }
 
+    /// Signs the message under the domain separation tag `dst`, see `SecretKey::sign_with_dst`.
+    ///
+    /// The shares combine with `PublicKeySet::combine_signatures` as usual; the result verifies
+    /// with `PublicKey::verify_with_dst` and the same tag.
+    pub fn sign_with_dst<M: AsRef<[u8]>>(&self, dst: &[u8], msg: M) -> SignatureShare {
+        SignatureShare(self.0.sign_with_dst(dst, msg))
+    }
+
+    /// Returns this share's contribution to the common coin of the given instance and round.
+    pub fn coin_share(&self, instance: &[u8], round: u64) -> CoinShare {
+        CoinShare(self.sign(coin_message(instance, round)))
+    }
+
+    /// Returns the matching public key share in G2, for the min-sig layout.
+    pub fn public_key_share_g2(&self) -> PublicKeyShareG2 {
+        PublicKeyShareG2(self.0.public_key_g2())
+    }
+
+    /// Signs the given message with a signature share in G1, for the min-sig layout.
+    pub fn sign_g1<M: AsRef<[u8]>>(&self, msg: M) -> SignatureShareG1 {
+        SignatureShareG1(self.0.sign_g1(msg))
+    }
+
     /// Returns a decryption share, or `None`, if the ciphertext isn't valid.
+    #[deprecated(note = "use `try_decrypt_share`, which reports why decryption failed")]
     pub fn decrypt_share(&self, ct: &Ciphertext) -> Option<DecryptionShare> {
+        self.try_decrypt_share(ct).ok()
+    }
+
+    /// Returns a decryption share, or `Error::InvalidCiphertext` if the ciphertext isn't valid.
+    pub fn try_decrypt_share(
+        &self,
+        ct: &Ciphertext,
+    ) -> std::result::Result<DecryptionShare, Error> {
+        if !ct.verify() {
+            return Err(Error::InvalidCiphertext);
+        }
+        Ok(self.decrypt_share_unchecked(ct))
+    }
+
+    /// Returns a decryption share with a proof that it was computed with this key share, or
+    /// `Error::InvalidCiphertext` if the ciphertext isn't valid.
+    ///
+    /// The proof is checked with `PublicKeyShare::verify_proven_share`, without pairings.
+    pub fn decrypt_share_proven<R: RngCore>(
+        &self,
+        ct: &Ciphertext,
+        rng: &mut R,
+    ) -> std::result::Result<ProvenDecryptionShare, Error> {
+        if !ct.verify() {
+            return Err(Error::InvalidCiphertext);
+        }
+        let pk = self.public_key_share();
+        Ok(ProvenDecryptionShare::new(
+            rng,
+            &(self.0).0,
+            &(pk.0).0,
+            ct.u(),
+        ))
+    }
+
+    /// Returns a decryption share of a ciphertext created by `PublicKey::encrypt_with_aad`, or
+    /// `Error::InvalidCiphertext` if it isn't valid for the associated data `aad`.
+    pub fn decrypt_share_with_aad<A: AsRef<[u8]>>(
+        &self,
+        ct: &Ciphertext,
+        aad: A,
+    ) -> std::result::Result<DecryptionShare, Error> {
+        if !ct.verify_with_aad(aad) {
+            return Err(Error::InvalidCiphertext);
+        }
+        Ok(self.decrypt_share_unchecked(ct))
+    }
+
+    /// Returns a decryption share of a ciphertext that has already been verified, skipping the
+    /// pairing check.
+    pub fn decrypt_share_verified(&self, ct: &VerifiedCiphertext) -> DecryptionShare {
+        self.decrypt_share_unchecked(ct)
+    }
+
+    /// Returns a decryption share of the ciphertext without verifying it.
+    ///
+    /// This saves the two pairings of `try_decrypt_share`, e.g. when a coordinator verified the
+    /// ciphertext once before handing it to every share holder. The caller must have checked it
+    /// with `Ciphertext::verify`: the share of an invalid ciphertext is a decryption oracle for
+    /// whoever forged it. Prefer `decrypt_share_verified`, which has the type system enforce this.
+    pub fn decrypt_share_unchecked(&self, ct: &Ciphertext) -> DecryptionShare {
+        DecryptionShare(ct.u() * ((self.0).0))
+    }
+
+    /// Returns a share of the re-encryption of `ct` to `target`, or `None`, if the ciphertext
+    /// isn't valid.
+    ///
+    /// Any `threshold + 1` of these shares can be combined with
+    /// `PublicKeySet::combine_reencryption_shares` into a ciphertext that `target`'s secret key
+    /// can decrypt. The combiner learns nothing about the plaintext, as long as no more than
+    /// `threshold` of the share holders collude with it: this is the same honest-majority
+    /// assumption threshold decryption relies on.
+    pub fn reencrypt_share(&self, ct: &Ciphertext, target: &PublicKey) -> Option<ReKeyShare> {
+        self.reencrypt_share_with_rng(&mut OsRng, ct, target)
+    }
+
+    /// Returns a share of the re-encryption of `ct` to `target`, using `rng` for the mask.
+    pub fn reencrypt_share_with_rng<R: RngCore>(
+        &self,
+        rng: &mut R,
+        ct: &Ciphertext,
+        target: &PublicKey,
+    ) -> Option<ReKeyShare> {
         if !ct.verify() {
             return None;
         }
-        Some(DecryptionShare(ct.0 * ((self.0).0)))
+        let rho = Scalar::random(rng);
+        let masked = ct.u() * ((self.0).0) + target.0 * rho;
+        Some(ReKeyShare(masked, G1Affine::generator() * rho))
     }
 
     pub fn from_mut(scalar: &mut Scalar) -> Self {
         SecretKeyShare(SecretKey::from_mut(scalar))
     }
+
+    /// Returns the little-endian byte encoding of the secret key share.
+    ///
+    /// The caller is responsible for clearing the returned bytes once they are no longer needed.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    /// Returns the big-endian byte encoding of the secret key share.
+    ///
+    /// The caller is responsible for clearing the returned bytes once they are no longer needed.
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        self.0.to_be_bytes()
+    }
+
+    /// Returns the little-endian byte encoding of the secret key share, in a buffer that is
+    /// overwritten with zeros when dropped.
+    pub fn to_bytes_zeroizing(&self) -> Zeroizing<Vec<u8>> {
+        self.0.to_bytes_zeroizing()
+    }
+
+    /// Returns the big-endian byte encoding of the secret key share, in a buffer that is
+    /// overwritten with zeros when dropped.
+    pub fn to_be_bytes_zeroizing(&self) -> Zeroizing<Vec<u8>> {
+        self.0.to_be_bytes_zeroizing()
+    }
+
+    /// Decodes a secret key share from its big-endian byte encoding.
+    pub fn from_be_bytes(bytes: &[u8; 32]) -> Result<Self> {
+        SecretKey::from_be_bytes(bytes).map(SecretKeyShare)
+    }
+
+    /// Decodes a secret key share from its little-endian byte encoding.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self> {
+        let mut scalar: Scalar = Option::from(Scalar::from_bytes(bytes))
+            .ok_or_else(|| anyhow!("Invalid secret key share encoding"))?;
+        Ok(SecretKeyShare::from_mut(&mut scalar))
+    }
+}
+
+#[cfg(feature = "secrecy")]
+impl ExposeSecret<Scalar> for SecretKeyShare {
+    fn expose_secret(&self) -> &Scalar {
+        self.0.expose_secret()
+    }
+}
+
+#[cfg(feature = "secrecy")]
+impl SecretKeyShare {
+    /// Decodes a secret key share from its wrapped little-endian byte encoding.
+    pub fn from_secret_bytes(bytes: &SecretBox<[u8; 32]>) -> Result<Self> {
+        SecretKeyShare::from_bytes(bytes.expose_secret())
+    }
+
+    /// Consumes the share and returns its little-endian byte encoding, wrapped so that it is
+    /// cleared when dropped.
+    pub fn into_secret_bytes(self) -> SecretSlice<u8> {
+        self.0.into_secret_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretKeySet;
+
+    #[test]
+    fn test_zeroize() {
+        let zero_share = SecretKeyShare::from_mut(&mut Scalar::zero());
+
+        let mut share = SecretKeyShare::random(&mut rand::thread_rng());
+        assert_ne!(zero_share, share);
+
+        share.zeroize();
+        assert_eq!(zero_share, share);
+    }
+
+    #[test]
+    fn verify_against() {
+        let mut rng = rand::thread_rng();
+        let sk_set = SecretKeySet::random(2, &mut rng);
+        let pk_set = sk_set.public_keys();
+        for i in 0..5 {
+            let share = sk_set.secret_key_share(i);
+            assert!(share.verify_against(&pk_set, i));
+            assert!(!share.verify_against(&pk_set, i + 1));
+        }
+        let other_pk_set = SecretKeySet::random(2, &mut rng).public_keys();
+        assert!(!sk_set.secret_key_share(0).verify_against(&other_pk_set, 0));
+        // A placeholder share is rejected instead of hitting the zero key assertion.
+        assert!(!SecretKeyShare::default().verify_against(&pk_set, 0));
+    }
+
+    #[test]
+    fn random_and_default() {
+        let mut rng = rand::thread_rng();
+        let share = SecretKeyShare::random(&mut rng);
+        assert!(!share.is_zero());
+        assert_ne!(share, SecretKeyShare::random(&mut rng));
+
+        // The default is the zero share, and the same every time.
+        let default = SecretKeyShare::default();
+        assert!(default.is_zero());
+        assert_eq!(SecretKeyShare::from(SecretKey::default()), default);
+        assert_eq!(default, SecretKeyShare::default());
+
+        #[derive(Default)]
+        struct Node {
+            share: SecretKeyShare,
+        }
+        assert!(Node::default().share.is_zero());
+    }
+
+    #[test]
+    fn from_mut_clears_input() {
+        let mut scalar = Scalar::random(rand::thread_rng());
+        let expected = scalar;
+        let share = SecretKeyShare::from_mut(&mut scalar);
+        assert_eq!(Scalar::zero(), scalar);
+        assert_eq!(expected, share.as_secret_key().0);
+    }
+
+    #[cfg(not(feature = "debug-secrets"))]
+    #[test]
+    fn redacted_debug() {
+        let share = SecretKeyShare::random(&mut rand::thread_rng());
+        let scalar = format!("{}", (share.0).0);
+        for output in &[format!("{:?}", share), format!("{}", share)] {
+            assert_eq!("SecretKeyShare(<redacted>)", output);
+        }
+        assert_eq!(format!("SecretKeyShare({})", scalar), share.reveal());
+        // Derived `Debug` output of types containing shares is redacted too.
+        assert!(!format!("{:?}", Some(&share)).contains(&scalar));
+    }
+
+    #[test]
+    fn from_secret_key() {
+        let sk = SecretKey::random();
+        let bytes = sk.to_bytes();
+        let pk = sk.public_key();
+        let share = SecretKeyShare::from(sk);
+        assert_eq!(bytes, share.as_secret_key().to_bytes());
+        assert_eq!(PublicKeyShare(pk), share.public_key_share());
+
+        let msg = b"Rip and tear, until it's done";
+        assert_eq!(share.as_secret_key().sign(msg), share.sign(msg).0);
+        let back: SecretKeyShare = SecretKey::from_bytes(&bytes).into();
+        assert_eq!(share, back);
+    }
 }
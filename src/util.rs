@@ -1,12 +1,19 @@
 use crate::into_scalar::IntoScalar;
+use crate::{Error, Interpolator};
+use anyhow::{bail, Result};
+#[cfg(feature = "hash-to-curve")]
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
 use bls12_381::Scalar;
 use bls12_381::{G1Affine, G1Projective, G2Affine, G2Projective};
+use ff::Field;
 use group::{Curve, Group};
 use rand::distributions::Standard;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaChaRng;
+use sha2::{Digest, Sha256};
 use std::borrow::Borrow;
 use std::cmp::Ordering;
+use std::fmt;
 use std::iter::once;
 use std::ops::{AddAssign, Mul};
 use tiny_keccak::{Hasher, Sha3};
@@ -21,31 +28,256 @@ pub fn sha3_256(data: &[u8]) -> [u8; 32] {
     output
 }
 
+/// The domain separation tag used by `hash_g2` with the `hash-to-curve` feature.
+pub const HASH_G2_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// The domain separation tag used by `hash_g1_g2` with the `hash-to-curve` feature.
+pub const HASH_G1_G2_DST: &[u8] = b"RUST_TC_BLS12381G2_XMD:SHA-256_SSWU_RO_G1G2_";
+
 /// Returns a hash of the given message in `G2Affine` space.
+///
+/// With the `hash-to-curve` feature this is `hash_to_g2(msg, HASH_G2_DST)`.
 pub fn hash_g2<M: AsRef<[u8]>>(msg: M) -> G2Projective {
-    let digest = sha3_256(msg.as_ref());
+    base_hash_g2(msg.as_ref(), HASH_G2_DST)
+}
+
+/// Returns a hash of the message in G2 under the domain separation tag `dst`, so that signatures
+/// in one protocol can't be replayed in another.
+///
+/// By default, the point is seeded with `expand_message_xmd(msg, dst, 32)` instead of the SHA3
+/// digest that `hash_g2` uses, so no message passed to `hash_g2` gives the same point. With the
+/// `hash-to-curve` feature, the tag is used as the RFC 9380 tag: `hash_to_g2(msg, dst)`.
+pub fn hash_g2_with_dst<M: AsRef<[u8]>>(dst: &[u8], msg: M) -> G2Projective {
+    if cfg!(feature = "hash-to-curve") {
+        return base_hash_g2(msg.as_ref(), dst);
+    }
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&expand_message_xmd(msg.as_ref(), dst, 32));
+    G2Projective::random(&mut ChaChaRng::from_seed(seed))
+}
+
+/// Stands in for secret values in `Debug` output, unless the `debug-secrets` feature is enabled.
+pub(crate) struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
+
+/// Returns the domain separation tag that binds a signature to a consensus epoch and view, see
+/// `SecretKey::sign_with_context`.
+pub(crate) fn context_dst(epoch: u64, view: u64) -> Vec<u8> {
+    let mut dst = b"RUST_TC_CONTEXT_V1_".to_vec();
+    dst.extend_from_slice(&epoch.to_be_bytes());
+    dst.extend_from_slice(&view.to_be_bytes());
+    dst
+}
+
+#[cfg(not(feature = "hash-to-curve"))]
+fn base_hash_g2(msg: &[u8], _dst: &[u8]) -> G2Projective {
+    let digest = sha3_256(msg);
     G2Projective::random(&mut ChaChaRng::from_seed(digest))
 }
 
+#[cfg(feature = "hash-to-curve")]
+fn base_hash_g2(msg: &[u8], dst: &[u8]) -> G2Projective {
+    hash_to_g2(msg, dst)
+}
+
+/// Hashes a message to G2 with the `BLS12381G2_XMD:SHA-256_SSWU_RO_` suite from RFC 9380.
+///
+/// This is constant time and compatible with other BLS implementations. `dst` is a domain
+/// separation tag that should be unique to the caller's protocol.
+#[cfg(feature = "hash-to-curve")]
+pub fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2Projective {
+    <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(msg, dst)
+}
+
+/// Returns a hash of the given message in `G1Affine` space.
+pub fn hash_g1<M: AsRef<[u8]>>(msg: M) -> G1Projective {
+    let digest = sha3_256(msg.as_ref());
+    G1Projective::random(&mut ChaChaRng::from_seed(digest))
+}
+
+/// Hashes a message to a scalar, as `hash_to_field` with `count = 1` in RFC 9380.
+///
+/// The message is expanded with `expand_message_xmd` and SHA-256 into 48 bytes, which are reduced
+/// modulo the group order; the result is statistically close to uniform. `dst` is a domain
+/// separation tag that should be unique to the caller's protocol, so that its hashes are
+/// independent from every other use of this function.
+pub fn hash_to_scalar(msg: &[u8], dst: &[u8]) -> Scalar {
+    let okm = expand_message_xmd(msg, dst, 48);
+    let mut wide = [0u8; 64];
+    for (w, b) in wide.iter_mut().zip(okm.iter().rev()) {
+        *w = *b;
+    }
+    Scalar::from_bytes_wide(&wide)
+}
+
+/// Expands a message into `len` pseudorandom bytes with SHA-256, following `expand_message_xmd`
+/// in RFC 9380. Tags longer than 255 bytes are hashed first, as the RFC specifies.
+///
+/// # Panics
+///
+/// Panics if `len` is more than `255 * 32` bytes.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    const B_IN_BYTES: usize = 64;
+    const B_OUT_BYTES: usize = 32;
+
+    let ell = (len + B_OUT_BYTES - 1) / B_OUT_BYTES;
+    assert!(ell <= 255, "expand_message_xmd output too long: {}", len);
+
+    let long_dst;
+    let dst = if dst.len() > 255 {
+        long_dst = Sha256::new()
+            .chain(b"H2C-OVERSIZE-DST-")
+            .chain(dst)
+            .finalize();
+        &long_dst[..]
+    } else {
+        dst
+    };
+    let dst_len = [dst.len() as u8];
+
+    let b_0 = Sha256::new()
+        .chain([0u8; B_IN_BYTES])
+        .chain(msg)
+        .chain((len as u16).to_be_bytes())
+        .chain([0u8])
+        .chain(dst)
+        .chain(dst_len)
+        .finalize();
+    let mut b_i = Sha256::new()
+        .chain(b_0)
+        .chain([1u8])
+        .chain(dst)
+        .chain(dst_len)
+        .finalize();
+
+    let mut out = Vec::with_capacity(ell * B_OUT_BYTES);
+    out.extend_from_slice(&b_i);
+    for i in 2..=ell {
+        let mut xored = b_0;
+        for (x, b) in xored.iter_mut().zip(b_i.iter()) {
+            *x ^= b;
+        }
+        b_i = Sha256::new()
+            .chain(xored)
+            .chain([i as u8])
+            .chain(dst)
+            .chain(dst_len)
+            .finalize();
+        out.extend_from_slice(&b_i);
+    }
+    out.truncate(len);
+    out
+}
+
 /// Returns the bitwise xor of `bytes` with a sequence of pseudorandom bytes determined by `g1`.
+///
+/// The pseudorandom stream is unbounded, so the output always has exactly `bytes.len()` bytes:
+/// empty input yields empty output, and no input is too long to be fully masked.
 pub fn xor_with_hash(g1: G1Projective, bytes: &[u8]) -> Vec<u8> {
-    let digest = sha3_256(g1.to_affine().to_compressed().as_ref());
+    let mut out = vec![0u8; bytes.len()];
+    xor_with_hash_into(g1, bytes, &mut out);
+    out
+}
+
+/// Writes `xor_with_hash(g1, bytes)` into the first `bytes.len()` bytes of `out`.
+///
+/// The output is written in place, so no intermediate copy of a plaintext is left behind in
+/// memory that was reallocated or dropped.
+///
+/// # Panics
+///
+/// Panics if `out` is shorter than `bytes`.
+pub(crate) fn xor_with_hash_into(g1: G1Projective, bytes: &[u8], out: &mut [u8]) {
+    for (o, b) in out[..bytes.len()]
+        .iter_mut()
+        .zip(xor_with_hash_iter(g1, bytes))
+    {
+        *o = b;
+    }
+}
+
+/// Returns the bytes of `xor_with_hash(g1, bytes)` lazily, one at a time.
+pub fn xor_with_hash_iter(g1: G1Projective, bytes: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    let mut digest = sha3_256(g1.to_affine().to_compressed().as_ref());
     let rng = ChaChaRng::from_seed(digest);
+    digest.zeroize();
     let xor = |(a, b): (u8, &u8)| a ^ b;
-    rng.sample_iter(&Standard).zip(bytes).map(xor).collect()
+    rng.sample_iter(&Standard).zip(bytes).map(xor)
 }
 
 /// Returns a hash of the group element and message, in the second group.
 pub fn hash_g1_g2<M: AsRef<[u8]>>(g1: G1Projective, msg: M) -> G2Projective {
+    hash_g1_affine_g2(&g1.to_affine(), msg.as_ref(), &[])
+}
+
+/// Returns a hash of the group element, message and associated data, in the second group.
+///
+/// The associated data is appended with a length prefix. Empty associated data gives the same
+/// result as `hash_g1_g2`.
+pub fn hash_g1_g2_aad<M: AsRef<[u8]>>(g1: G1Projective, msg: M, aad: &[u8]) -> G2Projective {
+    hash_g1_affine_g2(&g1.to_affine(), msg.as_ref(), aad)
+}
+
+/// Returns `hash_g1_g2_aad` of a point that is already affine, so callers that need the affine
+/// point anyway only convert it once.
+pub(crate) fn hash_g1_affine_g2(g1: &G1Affine, msg: &[u8], aad: &[u8]) -> G2Projective {
     // If the message is large, hash it, otherwise copy it.
     // TODO: Benchmark and optimize the threshold.
-    let mut msg = if msg.as_ref().len() > 64 {
-        sha3_256(msg.as_ref()).to_vec()
+    let mut msg = if msg.len() > 64 {
+        sha3_256(msg).to_vec()
     } else {
-        msg.as_ref().to_vec()
+        msg.to_vec()
     };
-    msg.extend(g1.to_affine().to_compressed().as_ref());
-    hash_g2(&msg)
+    msg.extend(g1.to_compressed().as_ref());
+    if !aad.is_empty() {
+        msg.extend(&(aad.len() as u64).to_be_bytes());
+        msg.extend(aad);
+    }
+    base_hash_g2(&msg, HASH_G1_G2_DST)
+}
+
+/// Returns the value at `0` of the polynomial of degree `t` through the given `(index, value)`
+/// samples, where sample `i` is the polynomial's value at `i + 1`. Only the first `t + 1` samples
+/// are used.
+///
+/// This works in any group, so it combines signature shares as well as decryption shares. It is a
+/// one-shot `Interpolator`: use that directly to reuse the weights for the same indices.
+pub fn interpolate_at_zero<G, B, T, I>(t: usize, items: I) -> Result<G>
+where
+    G: Group<Scalar = Scalar>,
+    I: IntoIterator<Item = (T, B)>,
+    T: IntoScalar,
+    B: Borrow<G>,
+{
+    let (indices, values): (Vec<T>, Vec<G>) = items
+        .into_iter()
+        .take(t + 1)
+        .map(|(i, sample)| (i, *sample.borrow()))
+        .unzip();
+    if indices.len() <= t {
+        bail!("need t+1 = {} shares, got {}", t + 1, indices.len())
+    }
+    Interpolator::new(&indices)?.interpolate_group(&values)
+}
+
+/// Returns the Lagrange coefficients at `0` for the given share indices, where share `i` is the
+/// value at `i + 1`.
+///
+/// Combining the shares with these coefficients gives the value at `0`. They only depend on the
+/// indices, so they can be computed once and reused. These are the weights of an `Interpolator`,
+/// which can also do the combining, e.g. with `PublicKeySet::combine_signatures_with_coeffs`.
+/// Fails if `indices` contains duplicates.
+#[deprecated(note = "use `Interpolator::new(indices)?.weights()`, which can also combine shares")]
+pub fn lagrange_coefficients_at_zero(indices: &[usize]) -> Result<Vec<Scalar>> {
+    if indices.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(Interpolator::new(indices)?.weights().to_vec())
 }
 
 /// Overwrites a single field element with zeros.
@@ -60,6 +292,37 @@ pub fn clear_scalar(scalar: &mut Scalar) {
     fr_repr[3].zeroize();
 }
 
+/// Returns the lowercase, `0x`-prefixed hex encoding of `bytes`, as used by Ethereum tooling.
+pub(crate) fn to_eth_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + 2 * bytes.len());
+    hex.push_str("0x");
+    for b in bytes {
+        hex.push_str(&format!("{:02x}", b));
+    }
+    hex
+}
+
+/// Decodes a `0x`-prefixed hex string. Both upper and lowercase digits are accepted.
+pub(crate) fn from_eth_hex(hex: &str) -> Result<Vec<u8>> {
+    let digits = match hex.strip_prefix("0x") {
+        Some(digits) => digits.as_bytes(),
+        None => bail!("Hex string must start with 0x"),
+    };
+    if digits.len() % 2 != 0 {
+        bail!("Hex string has an odd number of digits")
+    }
+    let nibble = |c: u8| match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => bail!("Invalid hex digit {:?}", c as char),
+    };
+    digits
+        .chunks(2)
+        .map(|pair| Ok(nibble(pair[0])? << 4 | nibble(pair[1])?))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,6 +339,154 @@ mod tests {
         clear_scalar(&mut scalar);
         assert_eq!(scalar, Scalar::zero());
     }
+
+    #[test]
+    fn xor_with_hash_empty() {
+        let g1 = G1Projective::random(&mut thread_rng());
+        assert!(xor_with_hash(g1, &[]).is_empty());
+    }
+
+    #[test]
+    fn xor_with_hash_large() {
+        let g1 = G1Projective::random(&mut thread_rng());
+        let bytes = vec![0u8; 4 * 1024 * 1024];
+        let masked = xor_with_hash(g1, &bytes);
+        assert_eq!(bytes.len(), masked.len());
+        // The stream keeps going to the very end: the tail is masked, not copied.
+        assert_ne!(&bytes[bytes.len() - 64..], &masked[masked.len() - 64..]);
+        assert_eq!(bytes, xor_with_hash(g1, &masked));
+    }
+
+    #[test]
+    fn interpolate_duplicate_indices() {
+        let g = G2Projective::generator();
+        let samples = vec![(0, g), (1, g.double()), (0, g)];
+        assert!(interpolate_at_zero::<G2Projective, _, _, _>(2, samples).is_err());
+        // Without the check, `[0, 0]` would get the weights `[1, 1]` and combine to `2 * g`.
+        let samples = vec![(0, g), (0, g)];
+        assert!(interpolate_at_zero::<G2Projective, _, _, _>(1, samples).is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn lagrange_coefficients_at_zero_weights() {
+        let indices = [4, 0, 7];
+        assert_eq!(
+            Interpolator::new(&indices).unwrap().weights(),
+            &lagrange_coefficients_at_zero(&indices).unwrap()[..]
+        );
+        assert!(lagrange_coefficients_at_zero(&[]).unwrap().is_empty());
+        assert!(lagrange_coefficients_at_zero(&[0, 0]).is_err());
+    }
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn expand_message_xmd_vectors() {
+        // RFC 9380, appendix K.1.
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(
+            from_hex("68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"),
+            expand_message_xmd(b"", dst, 0x20)
+        );
+        assert_eq!(
+            from_hex("d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"),
+            expand_message_xmd(b"abc", dst, 0x20)
+        );
+        assert_eq!(100, expand_message_xmd(b"abc", dst, 100).len());
+    }
+
+    #[cfg(feature = "hash-to-curve")]
+    #[test]
+    fn hash_to_g2_vectors() {
+        // RFC 9380, appendix J.10.1. Uncompressed encodings of `hash_to_g2(msg, dst)`.
+        let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+        let cases: [(&[u8], &str); 3] = [
+            (
+                b"",
+                concat!(
+                    "05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d",
+                    "0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a",
+                    "12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6",
+                    "0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92",
+                ),
+            ),
+            (
+                b"abc",
+                concat!(
+                    "139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8",
+                    "02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6",
+                    "00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16",
+                    "1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48",
+                ),
+            ),
+            (
+                b"abcdef0123456789",
+                concat!(
+                    "190d119345b94fbd15497bcba94ecf7db2cbfd1e1fe7da034d26cbba169fb3968288b3fafb265f9ebd380512a71c3f2c",
+                    "121982811d2491fde9ba7ed31ef9ca474f0e1501297f68c298e9f4c0028add35aea8bb83d53c08cfc007c1e005723cd0",
+                    "0bb5e7572275c567462d91807de765611490205a941a5a6af3b1691bfe596c31225d3aabdf15faff860cb4ef17c7c3be",
+                    "05571a0f8d3c08d094576981f4a3b8eda0a8e771fcdcc8ecceaf1356a6acf17574518acb506e435b639353c2e14827c8",
+                ),
+            ),
+        ];
+        for (msg, expected) in cases.iter() {
+            let hash = hash_to_g2(msg, dst).to_affine().to_uncompressed();
+            assert_eq!(from_hex(expected), hash.to_vec());
+        }
+
+        // The signing and encryption hashes use their own tags.
+        let msg = b"Rip and tear, until it's done";
+        assert_eq!(hash_to_g2(msg, HASH_G2_DST), hash_g2(msg));
+        let g1 = G1Projective::generator();
+        assert_ne!(hash_g1_g2(g1, msg), hash_g2(msg));
+    }
+
+    #[test]
+    fn hash_to_scalar_known_answers() {
+        // Little-endian scalar encodings. These must never change.
+        let dst = b"rust-tc-test-v1";
+        let expected = [
+            149, 216, 222, 136, 55, 227, 137, 210, 55, 204, 180, 233, 104, 251, 192, 120, 227, 110,
+            99, 164, 135, 106, 212, 101, 111, 254, 123, 242, 112, 198, 10, 84,
+        ];
+        assert_eq!(expected, hash_to_scalar(b"", dst).to_bytes());
+
+        let msg = b"Rip and tear, until it's done";
+        let expected = [
+            154, 249, 242, 230, 174, 67, 140, 94, 21, 53, 102, 155, 22, 154, 20, 10, 5, 17, 37,
+            148, 186, 211, 138, 99, 26, 231, 232, 203, 82, 84, 188, 95,
+        ];
+        assert_eq!(expected, hash_to_scalar(msg, dst).to_bytes());
+
+        let expected = [
+            54, 118, 225, 51, 38, 228, 27, 119, 39, 37, 224, 164, 57, 120, 27, 213, 249, 161, 48,
+            79, 33, 127, 197, 123, 191, 3, 105, 9, 118, 245, 40, 32,
+        ];
+        assert_eq!(
+            expected,
+            hash_to_scalar(msg, b"rust-tc-other-v1").to_bytes()
+        );
+    }
+
+    #[test]
+    fn hash_to_scalar_uniformity() {
+        let count = 2000;
+        let scalars: Vec<_> = (0..count as u32)
+            .map(|i| hash_to_scalar(&i.to_be_bytes(), b"rust-tc-test-v1"))
+            .collect();
+        let odd = scalars.iter().filter(|s| s.to_bytes()[0] & 1 == 1).count();
+        assert!(odd > count * 2 / 5 && odd < count * 3 / 5, "{} odd", odd);
+        let mut encoded: Vec<_> = scalars.iter().map(|s| s.to_bytes()).collect();
+        encoded.sort_unstable();
+        encoded.dedup();
+        assert_eq!(count, encoded.len());
+    }
 }
 
 /// Compares two curve elements and returns their `Ordering`.
@@ -107,6 +518,14 @@ pub fn coeff_pos(i: usize, j: usize) -> Option<usize> {
     i.checked_add(j.checked_mul(j.checked_add(1)?)? / 2)
 }
 
+/// Returns the number of coefficients of a symmetric bivariate polynomial of the given degree, or
+/// an `Error::DegreeOverflow` if it can't be represented as a `usize`.
+pub fn try_coeff_count(degree: usize) -> Result<usize> {
+    coeff_pos(degree, degree)
+        .and_then(|l| l.checked_add(1))
+        .ok_or_else(|| Error::DegreeOverflow.into())
+}
+
 /// Returns the `0`-th to `degree`-th power of `x`.
 pub fn powers<T: IntoScalar>(into_x: T, degree: usize) -> Vec<Scalar> {
     let x = into_x.into_scalar();
@@ -0,0 +1,31 @@
+use crate::Ciphertext;
+use std::ops::Deref;
+
+/// A ciphertext that has passed `Ciphertext::verify`.
+///
+/// It can only be obtained from `Ciphertext::into_verified`, so functions that take it, like
+/// `SecretKey::decrypt_verified` and `SecretKeyShare::decrypt_share_verified`, can skip the
+/// pairing check.
+#[derive(PartialEq, Eq, Debug, Clone, Hash, PartialOrd, Ord)]
+pub struct VerifiedCiphertext(pub(crate) Ciphertext);
+
+impl VerifiedCiphertext {
+    /// Returns the verified ciphertext.
+    pub fn into_inner(self) -> Ciphertext {
+        self.0
+    }
+}
+
+impl Deref for VerifiedCiphertext {
+    type Target = Ciphertext;
+
+    fn deref(&self) -> &Ciphertext {
+        &self.0
+    }
+}
+
+impl AsRef<Ciphertext> for VerifiedCiphertext {
+    fn as_ref(&self) -> &Ciphertext {
+        &self.0
+    }
+}
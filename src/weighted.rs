@@ -0,0 +1,149 @@
+use crate::{PublicKeySet, SecretKeySet, SecretKeyShare, SignatureShare};
+use std::ops::Range;
+
+/// A secret key set whose shares are distributed among participants according to their weights.
+///
+/// Participant `p` receives a contiguous block of `weights[p]` secret key shares, so the total
+/// number of shares handed out is the total weight. As with a plain `SecretKeySet`, any
+/// `threshold + 1` shares can sign: any group of participants whose weights sum to more than the
+/// threshold can sign together, and no group with a total weight of at most `threshold` can. The
+/// threshold should therefore be less than the total weight.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WeightedKeySet {
+    sk_set: SecretKeySet,
+    /// The first share index of each participant, followed by the total weight.
+    offsets: Vec<usize>,
+}
+
+impl WeightedKeySet {
+    /// Creates a weighted key set, assigning `weights[p]` shares of `sk_set` to participant `p`.
+    pub fn new(sk_set: SecretKeySet, weights: &[usize]) -> Self {
+        let mut offsets = Vec::with_capacity(weights.len() + 1);
+        let mut offset = 0;
+        offsets.push(offset);
+        for weight in weights {
+            offset += weight;
+            offsets.push(offset);
+        }
+        WeightedKeySet { sk_set, offsets }
+    }
+
+    /// Returns the threshold: participants with a total weight of `threshold + 1` can sign.
+    pub fn threshold(&self) -> usize {
+        self.sk_set.threshold()
+    }
+
+    /// Returns the number of participants.
+    pub fn num_participants(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns the sum of all participants' weights.
+    pub fn total_weight(&self) -> usize {
+        self.offsets[self.num_participants()]
+    }
+
+    /// Returns the indices of the secret key shares of participant `p`.
+    pub fn share_indices(&self, p: usize) -> Range<usize> {
+        self.offsets[p]..self.offsets[p + 1]
+    }
+
+    /// Returns the public key set, against which combined signatures are verified as usual.
+    pub fn public_keys(&self) -> PublicKeySet {
+        self.sk_set.public_keys()
+    }
+
+    /// Returns all secret key shares of participant `p`.
+    pub fn weighted_share(&self, p: usize) -> WeightedKeyShare {
+        let shares = self
+            .share_indices(p)
+            .map(|i| (i, self.sk_set.secret_key_share(i)))
+            .collect();
+        WeightedKeyShare { shares }
+    }
+}
+
+/// The secret key shares of a single participant in a `WeightedKeySet`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WeightedKeyShare {
+    shares: Vec<(usize, SecretKeyShare)>,
+}
+
+impl WeightedKeyShare {
+    /// Returns the participant's weight, i.e. the number of shares they hold.
+    pub fn weight(&self) -> usize {
+        self.shares.len()
+    }
+
+    /// Signs the given message with each of the participant's shares.
+    pub fn sign<M: AsRef<[u8]>>(&self, msg: M) -> WeightedSignatureShare {
+        let shares = self
+            .shares
+            .iter()
+            .map(|(i, share)| (*i, share.sign(msg.as_ref())))
+            .collect();
+        WeightedSignatureShare(shares)
+    }
+}
+
+/// The signature shares of a single participant, with their share indices.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WeightedSignatureShare(pub Vec<(usize, SignatureShare)>);
+
+impl WeightedSignatureShare {
+    /// Returns the number of signature shares.
+    pub fn weight(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the signature shares with their indices, e.g. to combine with
+    /// `PublicKeySet::combine_signatures`.
+    pub fn shares(&self) -> impl Iterator<Item = (usize, &SignatureShare)> {
+        self.0.iter().map(|(i, share)| (*i, share))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weighted_threshold() {
+        let mut rng = rand::thread_rng();
+        let weights = [3, 1, 1, 2];
+        let w_set = WeightedKeySet::new(SecretKeySet::random(3, &mut rng), &weights);
+        let pk_set = w_set.public_keys();
+        assert_eq!(3, w_set.threshold());
+        assert_eq!(4, w_set.num_participants());
+        assert_eq!(7, w_set.total_weight());
+        assert_eq!(0..3, w_set.share_indices(0));
+        assert_eq!(3..4, w_set.share_indices(1));
+        assert_eq!(5..7, w_set.share_indices(3));
+
+        let msg = b"Rip and tear, until it's done";
+        let sig_shares: Vec<_> = (0..4).map(|p| w_set.weighted_share(p).sign(msg)).collect();
+        for (p, weight) in weights.iter().enumerate() {
+            assert_eq!(*weight, sig_shares[p].weight());
+        }
+
+        // The 3-weight participant alone is at the threshold, and can't sign.
+        assert!(pk_set
+            .combine_weighted_signatures(&sig_shares[..1])
+            .is_err());
+
+        // Together with a 1-weight participant, they cross it.
+        let sig = pk_set
+            .combine_weighted_signatures(&sig_shares[..2])
+            .expect("failed to combine");
+        assert!(pk_set.public_key().unwrap().verify(&sig, msg));
+
+        let sig = pk_set
+            .combine_weighted_signatures(&sig_shares[1..])
+            .expect("failed to combine");
+        assert!(pk_set.public_key().unwrap().verify(&sig, msg));
+
+        assert!(pk_set
+            .combine_weighted_signatures(&sig_shares[1..3])
+            .is_err());
+    }
+}
@@ -54,7 +54,10 @@ mod tests {
 
         // Alice receives Bob's encrypted message. She decrypts the message using her secret key. She
         // then verifies that the signature of the plaintext is valid using Bob's public key.
-        let decrypted = alice.sk.decrypt(&ciphertext).expect("Invalid ciphertext");
+        let decrypted = alice
+            .sk
+            .try_decrypt(&ciphertext)
+            .expect("Invalid ciphertext");
         let deserialized: SignedMsg =
             deserialize(&decrypted).expect("Failed to deserialize bytes to `SignedMsg`");
         assert!(bob.pk.verify(&deserialized.sig, &deserialized.msg));
@@ -43,6 +43,7 @@ mod tests {
                 let five = Poly::constant(5.into_scalar());
                 let wrong_poly = row_poly.clone() + x_pow_2 * five;
                 assert_ne!(wrong_poly.commitment(), row_commit);
+                assert!(!bi_commit.verify_row(m, &wrong_poly.commitment()));
 
                 // If `2 * faulty_num + 1` nodes confirm that they received a valid row, then at
                 // least `faulty_num + 1` honest ones did, and sent the correct values on to node
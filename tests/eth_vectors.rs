@@ -0,0 +1,55 @@
+// Checks our decoding of compressed points against the eth2 deserialization cases in
+// `data/eth_deserialization.txt`.
+
+use rust_tc::{PublicKey, SecretKey, Signature};
+
+const VECTORS: &str = include_str!("data/eth_deserialization.txt");
+
+#[test]
+fn eth_deserialization_vectors() {
+    let mut count = 0;
+    for line in VECTORS
+        .lines()
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+    {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (group, name, input, expected) = (fields[0], fields[1], fields[2], fields[3]);
+        let expected: bool = expected.parse().unwrap();
+        let hex = match group {
+            "G1" => PublicKey::from_eth_hex(input).map(|pk| pk.to_eth_hex()),
+            "G2" => Signature::from_eth_hex(input).map(|sig| sig.to_eth_hex()),
+            _ => panic!("unknown group {}", group),
+        };
+        assert_eq!(expected, hex.is_ok(), "{} {}", group, name);
+        // Decoding is canonical: accepted inputs encode back to themselves.
+        if let Ok(hex) = hex {
+            assert_eq!(input, hex, "{} {}", group, name);
+        }
+        count += 1;
+    }
+    assert_eq!(24, count);
+}
+
+#[test]
+fn eth_hex_roundtrip() {
+    let sk = SecretKey::random();
+    let pk = sk.public_key();
+    let sig = sk.sign("Rip and tear, until it's done");
+
+    let pk_hex = pk.to_eth_hex();
+    assert_eq!(2 + 2 * 48, pk_hex.len());
+    assert_eq!(pk, PublicKey::from_eth_hex(&pk_hex).unwrap());
+    assert_eq!(
+        pk,
+        PublicKey::from_eth_hex(&pk_hex.to_uppercase().replacen("0X", "0x", 1)).unwrap()
+    );
+
+    let sig_hex = sig.to_eth_hex();
+    assert_eq!(2 + 2 * 96, sig_hex.len());
+    assert_eq!(sig, Signature::from_eth_hex(&sig_hex).unwrap());
+
+    // The prefix is required, and only hex digits are accepted.
+    assert!(Signature::from_eth_hex(&sig_hex[2..]).is_err());
+    assert!(Signature::from_eth_hex(&format!("{}0", sig_hex)).is_err());
+    assert!(Signature::from_eth_hex(&sig_hex.replacen('0', "g", 1)).is_err());
+}
@@ -40,7 +40,7 @@ impl SecretSociety {
 
     // The secret society publishes its public-key to a publicly accessible key server.
     fn publish_public_key(&self) -> PublicKey {
-        self.pk_set.public_key()
+        self.pk_set.public_key().unwrap()
     }
 
     fn get_actor(&mut self, id: usize) -> &mut Actor {
@@ -109,7 +109,7 @@ impl DecryptionMeeting {
             self.ciphertext = Some(ciphertext.clone());
         }
 
-        let dec_share = actor.sk_share.decrypt_share(&ciphertext).unwrap();
+        let dec_share = actor.sk_share.try_decrypt_share(&ciphertext).unwrap();
         let dec_share_is_valid = actor
             .pk_share
             .verify_decryption_share(&dec_share, &ciphertext);